@@ -1,6 +1,8 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 
+use sljs::ast::expr;
 use sljs::runtime::{EsprimaParser, Runtime};
+use sljs::{Heap, JSObject, JSValue};
 
 fn esprima_eval() {
     let parser = EsprimaParser::new();
@@ -13,5 +15,21 @@ fn benchmark_esprimaparser(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benchmarks, benchmark_esprimaparser);
+fn benchmark_array_includes(c: &mut Criterion) {
+    let mut heap = Heap::new();
+    let numbers = JSObject::from_array(vec![JSValue::from(1); 1_000_000]);
+    let array_ref = heap.alloc(numbers);
+    heap.set_global("a", JSValue::from(array_ref)).unwrap();
+    let includes = expr::call(expr::memb(expr::id("a"), "includes"), vec![expr::lit(2.0)]);
+
+    c.bench_function("a.includes(2) over a million numbers", |b| {
+        b.iter(|| heap.evaluate(&includes).expect("a.includes(2)"))
+    });
+}
+
+criterion_group!(
+    benchmarks,
+    benchmark_esprimaparser,
+    benchmark_array_includes
+);
 criterion_main!(benchmarks);
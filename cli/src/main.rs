@@ -22,15 +22,20 @@ use atty::{self, Stream};
 
 use clap::Parser;
 use sljs::runtime::{EsprimaParser, JSONParser, NodejsParser, Parser as JSParser, Runtime};
-use sljs::source;
+use sljs::HeapBuilder;
 
 /// Reads stdin, parses and interprets it as one block.
 pub fn batch_main(sljs: &mut Runtime) -> io::Result<()> {
     let mut input = String::new();
     io::stdin().lock().read_to_string(&mut input)?;
 
-    let result = sljs.evaluate(&input)?;
-    println!("{}", sljs.string_from(result));
+    match sljs.evaluate(&input) {
+        Ok(result) => println!("{}", sljs.string_from(result)),
+        Err(err) => {
+            eprintln!("{}", sljs.format_error(&err));
+            std::process::exit(1);
+        }
+    }
     Ok(())
 }
 
@@ -60,12 +65,7 @@ pub fn repl_main(sljs: &mut Runtime) -> io::Result<()> {
 
         match sljs.evaluate(&input) {
             Ok(result) => println!("{}", sljs.string_from(result)),
-            Err(err) => {
-                eprintln!("{}", err);
-                if let Err(e) = source::print_callstack(&sljs.heap) {
-                    eprintln!("   Exception thrown while getting stack trace: {:?}", e);
-                }
-            }
+            Err(err) => eprintln!("{}", sljs.format_error(&err)),
         }
     }
 
@@ -94,6 +94,14 @@ struct Args {
     /// Expect ESTree AST as JSON input
     #[clap(short = 'J', long, action)]
     json: bool,
+
+    /// Make `eval()` throw instead of parsing code, leave out `process`
+    #[clap(long, action)]
+    sandbox: bool,
+
+    /// Log every interpreted statement to stderr
+    #[clap(long, action)]
+    trace: bool,
 }
 
 impl Args {
@@ -127,7 +135,10 @@ fn main() -> io::Result<()> {
         dbg!(&args.sources);
     }
 
-    let mut sljs = Runtime::load(parser)?;
+    let heap = HeapBuilder::new().process(!args.sandbox).build();
+    let mut sljs = Runtime::load_with_heap(parser, heap)?;
+    sljs.set_sandboxed(args.sandbox)?;
+    sljs.heap.set_trace(args.trace);
 
     if atty::is(Stream::Stdin) {
         repl_main(&mut sljs)
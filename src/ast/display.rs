@@ -60,6 +60,7 @@ impl ToSource for BinOp {
             Star => "*",
             Slash => "/",
             Percent => "%",
+            StarStar => "**",
             EqEq => "==",
             NotEq => "!=",
             EqEqEq => "===",
@@ -177,12 +178,17 @@ impl ToSource for Expr {
                     let mut s = String::new();
                     s.push('[');
                     for expr in arrexpr.0.iter() {
-                        let e = expr.to_source();
-                        s.push_str(e[0].as_str());
+                        if let Some(expr) = expr {
+                            let e = expr.to_source();
+                            s.push_str(e[0].as_str());
+                        }
                         s.push_str(", ");
                     }
                     s.pop();
-                    s.pop();
+                    // a trailing hole needs its comma: `[1, ,]`
+                    if arrexpr.0.last().unwrap().is_some() {
+                        s.pop();
+                    }
                     s.push(']');
                     vec![s]
                 }
@@ -190,6 +196,7 @@ impl ToSource for Expr {
             Expr::Member(membexpr) => membexpr.to_source(),
             Expr::Call(callexpr) => callexpr.to_source(),
             Expr::This => vec!["this".to_string()],
+            Expr::NewTarget => vec!["new.target".to_string()],
             /*
             Expr::Unary() => TODO
             Expr::Conditional() => TODO
@@ -333,6 +340,7 @@ impl ToSource for Stmt {
             //Label() => TODO
             //Throw() => TODO
             //Try() => TODO
+            Debugger => vec!["debugger;".to_string()],
             //Variable() => TODO
             //Function() => TODO
             _ => todo!("{:?}.to_source()", self),
@@ -397,6 +405,10 @@ mod test {
             r#"[2, "a", 2 + "a"]"#,
             array(vec![lit(2), lit("a"), add(2, "a")]),
         );
+        {
+            let holes = ArrayExpression(vec![Some(lit(1)), None, Some(lit(3)), None]);
+            assert_print!("[1, , 3, ,]", Expression::from(Expr::Array(holes)));
+        }
 
         assert_print!("sqr(12)", call(id("sqr"), vec![lit(12)]));
         assert_print!(
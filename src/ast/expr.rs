@@ -4,9 +4,9 @@
 
 use crate::prelude::*;
 
-use crate::{source, JSON};
+use crate::{source, JSBigInt, JSON};
 
-use super::stmt::{BlockStatement, FunctionDeclaration};
+use super::stmt::{BlockStatement, FunctionDeclaration, Statement};
 
 /// `Expression` represents an [`Expr`] together with its source span, if any.
 #[derive(Debug, Clone)]
@@ -59,7 +59,19 @@ pub enum Expr {
     Sequence(SequenceExpression),
     Function(FunctionExpression),
     This,
+    NewTarget,
     New(Box<NewExpression>),
+    Template(TemplateLiteral),
+    TaggedTemplate(Box<TaggedTemplateExpression>),
+    /// `ChainExpression`: the boundary of an optional chain, e.g. `a?.b.c`.
+    /// If any optional link is nullish, the whole chain is `undefined`.
+    Chain(Box<Expression>),
+    /// `a?.b` and `a?.[b]`, only inside [`Expr::Chain`]
+    OptionalMember(Box<MemberExpression>),
+    /// `f?.()`, only inside [`Expr::Chain`]
+    OptionalCall(Box<CallExpression>),
+    /// `...iterable`, only in [`ArrayExpression`] elements and call or `new` arguments
+    Spread(Box<Expression>),
 }
 
 impl<T> From<T> for Expr
@@ -113,28 +125,56 @@ impl From<CallExpression> for Expr {
     }
 }
 
+impl From<AssignmentExpression> for Expr {
+    fn from(assign: AssignmentExpression) -> Expr {
+        Expr::Assign(Box::new(assign))
+    }
+}
+
+impl From<FunctionExpression> for Expr {
+    fn from(funcexpr: FunctionExpression) -> Expr {
+        Expr::Function(funcexpr)
+    }
+}
+
+/// A literal value: `null`, a boolean, a number, a string or a bigint.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Literal(JSON); // TODO: change to JSValue
+pub enum Literal {
+    JSON(JSON), // TODO: change to JSValue
+    /// `42n`
+    BigInt(JSBigInt),
+}
 
 impl Literal {
     pub fn to_value(&self) -> JSValue {
-        JSValue::try_from(&self.0).expect("primitive JSON")
+        match self {
+            Literal::JSON(json) => JSValue::try_from(json).expect("primitive JSON"),
+            Literal::BigInt(n) => JSValue::BigInt(n.clone()),
+        }
     }
 
+    /// The ESTree `value`: bigints are `null` in JSON.
     pub fn to_json(&self) -> JSON {
-        self.0.clone()
+        match self {
+            Literal::JSON(json) => json.clone(),
+            Literal::BigInt(_) => JSON::Null,
+        }
     }
 }
 
 impl fmt::Display for Literal {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.0.is_null() {
+        let json = match self {
+            Literal::JSON(json) => json,
+            Literal::BigInt(n) => return write!(f, "{}n", n),
+        };
+        if json.is_null() {
             write!(f, "null")
-        } else if let Some(b) = self.0.as_bool() {
+        } else if let Some(b) = json.as_bool() {
             write!(f, "{}", b)
-        } else if let Some(n) = self.0.as_f64() {
+        } else if let Some(n) = json.as_f64() {
             write!(f, "{}", n)
-        } else if let Some(s) = self.0.as_str() {
+        } else if let Some(s) = json.as_str() {
             write!(f, "\"{}\"", s.escape_default())
         } else {
             panic!("Literal cannot be {:?}", self)
@@ -146,33 +186,38 @@ impl From<JSON> for Literal {
     fn from(json: JSON) -> Literal {
         assert!(!json.is_array());
         assert!(!json.is_object());
-        Literal(json)
+        Literal::JSON(json)
     }
 }
 
 impl From<bool> for Literal {
     fn from(b: bool) -> Self {
-        Literal(JSON::from(b))
+        Literal::JSON(JSON::from(b))
     }
 }
 impl From<f64> for Literal {
     fn from(n: f64) -> Self {
-        Literal(JSON::from(n))
+        Literal::JSON(JSON::from(n))
     }
 }
 impl From<i64> for Literal {
     fn from(n: i64) -> Self {
-        Literal(JSON::from(n))
+        Literal::JSON(JSON::from(n))
     }
 }
 impl From<&str> for Literal {
     fn from(s: &str) -> Self {
-        Literal(JSON::from(s))
+        Literal::JSON(JSON::from(s))
     }
 }
 impl From<JSString> for Literal {
     fn from(s: JSString) -> Self {
-        Literal(JSON::String(s.to_string()))
+        Literal::JSON(JSON::String(s.to_string()))
+    }
+}
+impl From<JSBigInt> for Literal {
+    fn from(n: JSBigInt) -> Self {
+        Literal::BigInt(n)
     }
 }
 
@@ -213,7 +258,7 @@ pub struct UpdateExpression(pub UpdOp, pub bool, pub Expression);
 pub struct CallExpression(pub Expression, pub Vec<Expression>);
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct ArrayExpression(pub Vec<Expression>);
+pub struct ArrayExpression(pub Vec<Option<Expression>>);
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ObjectExpression(pub Vec<(ObjectKey, Expression)>);
@@ -258,7 +303,7 @@ pub struct ConditionalExpression {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Function {
     pub id: Option<Identifier>,
-    pub params: Vec<Pattern>,           // cannot be a HashSet, needs order
+    pub params: Vec<Identifier>, // cannot be a HashSet, needs order
     pub variables: HashSet<Identifier>, // the set of local variables
     pub functions: Vec<FunctionDeclaration>, // the set of declared functions
     pub free_variables: HashSet<Identifier>,
@@ -273,12 +318,65 @@ pub struct FunctionExpression {
     pub func: Rc<Function>,
 }
 
-// TODO: enum { AssignmentPattern, Identifier, BindingPattern }
-pub type Pattern = Identifier;
+/// A binding target of a declaration: `x`, `[a, , b]` or `{a, b: c}`.
+// TODO: AssignmentPattern, RestElement
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    Identifier(Identifier),
+    /// `[a, , b]`: holes are `None`
+    Array(Vec<Option<Pattern>>),
+    /// `{a, b: [c], [key]: d}`: properties are read in order
+    Object(Vec<(ObjectKey, Pattern)>),
+}
+
+impl Pattern {
+    /// All the names this pattern binds, in the source order.
+    pub fn bound_names(&self) -> Vec<&Identifier> {
+        match self {
+            Pattern::Identifier(name) => vec![name],
+            Pattern::Array(elements) => (elements.iter().flatten())
+                .flat_map(|pattern| pattern.bound_names())
+                .collect(),
+            Pattern::Object(properties) => (properties.iter())
+                .flat_map(|(_, pattern)| pattern.bound_names())
+                .collect(),
+        }
+    }
+}
+
+impl From<Identifier> for Pattern {
+    fn from(name: Identifier) -> Pattern {
+        Pattern::Identifier(name)
+    }
+}
+
+impl From<&str> for Pattern {
+    fn from(name: &str) -> Pattern {
+        Pattern::Identifier(Identifier::from(name))
+    }
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct NewExpression(pub Expression, pub Vec<Expression>);
 
+/// `TemplateLiteral` is `` `a${x}b` ``: `quasis` always has one more element than `expressions`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateLiteral {
+    pub quasis: Vec<TemplateElement>,
+    pub expressions: Vec<Expression>,
+}
+
+/// A string chunk of a [`TemplateLiteral`]; `cooked` is `None` for invalid escapes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateElement {
+    pub cooked: Option<JSString>,
+    pub raw: JSString,
+}
+
+/// `TaggedTemplateExpression` is `` tag`a${x}b` ``
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaggedTemplateExpression(pub Expression, pub TemplateLiteral);
+
 /// Lists all possible binary operation for [`BinaryExpression`]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BinOp {
@@ -287,6 +385,7 @@ pub enum BinOp {
     Star,
     Slash,
     Percent,
+    StarStar,
     EqEq,
     NotEq,
     EqEqEq,
@@ -352,7 +451,7 @@ pub fn lit<V>(value: V) -> Expression
 where
     JSON: From<V>,
 {
-    Expression::from(Literal(JSON::from(value)))
+    Expression::from(Literal::JSON(JSON::from(value)))
 }
 
 /// make an [`Identifier`] from `name` (JS: `name`)
@@ -365,11 +464,23 @@ pub fn array<E>(exprs: Vec<E>) -> Expression
 where
     Expression: From<E>,
 {
-    let exprs = exprs.into_iter().map(Expression::from).collect();
+    let exprs = exprs
+        .into_iter()
+        .map(|e| Some(Expression::from(e)))
+        .collect();
     let expr = Expr::Array(ArrayExpression(exprs));
     Expression { expr, loc: None }
 }
 
+/// make an [`Expr::Spread`] of `iterable`, JS: `...iterable`
+pub fn spread<E>(iterable: E) -> Expression
+where
+    Expression: From<E>,
+{
+    let expr = Expr::Spread(Box::new(Expression::from(iterable)));
+    Expression { expr, loc: None }
+}
+
 /// make an empty [`ArrayExpression`], JS: `[]`
 pub fn empty_array() -> Expression {
     array::<Expression>(vec![])
@@ -451,3 +562,38 @@ where
     let callee = Expression::from(callee);
     CallExpression(callee, arguments).into()
 }
+
+/// make an [`AssignmentExpression`] (JS: `target = value`)
+pub fn assign<E1, E2>(target: E1, value: E2) -> Expression
+where
+    Expression: From<E1> + From<E2>,
+{
+    let target = Expression::from(target);
+    let value = Expression::from(value);
+    AssignmentExpression(target, None, value).into()
+}
+
+/// make an anonymous [`FunctionExpression`] (JS: `function (params...) { body... }`)
+///
+/// Its variables and function declarations are collected from `body`.
+/// Free variables are not: they are only informational.
+pub fn func(params: &[&str], body: Vec<Statement>) -> Expression {
+    let body = BlockStatement::from(body);
+    let (variables, functions) = body.hoisted_declarations();
+    let func = Function {
+        id: None,
+        params: params
+            .iter()
+            .map(|&param| Identifier::from(param))
+            .collect(),
+        variables,
+        functions,
+        free_variables: HashSet::new(),
+        body,
+        is_generator: false,
+        is_expression: false,
+        is_async: false,
+    };
+    let func = Rc::new(func);
+    FunctionExpression { func }.into()
+}
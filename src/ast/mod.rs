@@ -45,10 +45,11 @@ impl Eq for Program {}
 
 impl From<BlockStatement> for Program {
     fn from(blockstmt: BlockStatement) -> Program {
+        let (variables, functions) = blockstmt.hoisted_declarations();
         Program {
             body: blockstmt,
-            variables: HashSet::new(), // TODO: block analysis
-            functions: vec![],         // TODO: block analysis
+            variables,
+            functions,
         }
     }
 }
@@ -53,18 +53,47 @@ pub enum Stmt {
     Switch(SwitchStatement),
     For(Box<ForStatement>),
     ForIn(Box<ForInStatement>),
+    ForOf(Box<ForOfStatement>),
     Return(ReturnStatement),
     Break(BreakStatement),
     Continue(ContinueStatement),
     Label(Box<LabelStatement>),
     Throw(ThrowStatement),
     Try(TryStatement),
+    With(Box<WithStatement>),
+    Debugger,
 
     // TODO: move declarations out?
     Variable(VariableDeclaration),
     Function(FunctionDeclaration),
 }
 
+impl Stmt {
+    /// The ESTree-like name of the statement type
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Stmt::Empty => "EmptyStatement",
+            Stmt::Block(_) => "BlockStatement",
+            Stmt::Expr(_) => "ExpressionStatement",
+            Stmt::If(_) => "IfStatement",
+            Stmt::Switch(_) => "SwitchStatement",
+            Stmt::For(_) => "ForStatement",
+            Stmt::ForIn(_) => "ForInStatement",
+            Stmt::ForOf(_) => "ForOfStatement",
+            Stmt::Return(_) => "ReturnStatement",
+            Stmt::Break(_) => "BreakStatement",
+            Stmt::Continue(_) => "ContinueStatement",
+            Stmt::Label(_) => "LabeledStatement",
+            Stmt::Throw(_) => "ThrowStatement",
+            Stmt::Try(_) => "TryStatement",
+            Stmt::With(_) => "WithStatement",
+            Stmt::Debugger => "DebuggerStatement",
+            Stmt::Variable(_) => "VariableDeclaration",
+            Stmt::Function(_) => "FunctionDeclaration",
+        }
+    }
+}
+
 impl<E> From<E> for Stmt
 where
     Expression: From<E>,
@@ -94,6 +123,70 @@ impl From<ReturnStatement> for Stmt {
     }
 }
 
+impl From<IfStatement> for Stmt {
+    fn from(ifstmt: IfStatement) -> Stmt {
+        Stmt::If(Box::new(ifstmt))
+    }
+}
+
+impl From<ForStatement> for Stmt {
+    fn from(forstmt: ForStatement) -> Stmt {
+        Stmt::For(Box::new(forstmt))
+    }
+}
+
+impl Statement {
+    /// Adds `var` names and function declarations of the statement to `variables`/`functions`,
+    /// looking into nested statements, but not into nested functions.
+    fn collect_hoisted(
+        &self,
+        variables: &mut HashSet<Identifier>,
+        functions: &mut Vec<FunctionDeclaration>,
+    ) {
+        let mut collect = |stmt: &Statement| stmt.collect_hoisted(variables, functions);
+        match &self.stmt {
+            Stmt::Block(block) => block.body.iter().for_each(collect),
+            Stmt::If(stmt) => {
+                collect(&stmt.consequent);
+                stmt.alternate.iter().for_each(collect);
+            }
+            Stmt::Switch(stmt) => (stmt.cases.iter())
+                .flat_map(|case| case.consequent.iter())
+                .for_each(collect),
+            Stmt::For(stmt) => {
+                collect(&stmt.init);
+                collect(&stmt.body);
+            }
+            Stmt::ForIn(stmt) => {
+                if let ForInTarget::Var(decl) = &stmt.left {
+                    decl.collect_hoisted(variables);
+                }
+                stmt.body.collect_hoisted(variables, functions);
+            }
+            Stmt::ForOf(stmt) => {
+                if let ForInTarget::Var(decl) = &stmt.left {
+                    decl.collect_hoisted(variables);
+                }
+                stmt.body.collect_hoisted(variables, functions);
+            }
+            Stmt::Label(stmt) => collect(&stmt.1),
+            Stmt::Try(stmt) => {
+                stmt.block.body.iter().for_each(&mut collect);
+                if let Some(handler) = &stmt.handler {
+                    handler.body.body.iter().for_each(&mut collect);
+                }
+                if let Some(finalizer) = &stmt.finalizer {
+                    finalizer.body.iter().for_each(&mut collect);
+                }
+            }
+            Stmt::With(stmt) => collect(&stmt.body),
+            Stmt::Variable(decl) => decl.collect_hoisted(variables),
+            Stmt::Function(decl) => functions.push(decl.clone()),
+            _ => (),
+        }
+    }
+}
+
 // ==============================================
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ExpressionStatement {
@@ -120,6 +213,15 @@ pub struct VariableDeclaration {
     pub declarations: Vec<VariableDeclarator>,
 }
 
+impl VariableDeclaration {
+    fn collect_hoisted(&self, variables: &mut HashSet<Identifier>) {
+        if self.kind == DeclarationKind::Var {
+            let names = (self.declarations.iter()).flat_map(|decl| decl.name.bound_names());
+            variables.extend(names.cloned());
+        }
+    }
+}
+
 // ==============================================
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct FunctionDeclaration {
@@ -141,6 +243,20 @@ impl PartialEq for BlockStatement {
 }
 impl Eq for BlockStatement {}
 
+impl BlockStatement {
+    /// `var` names and function declarations in the block (nested functions excluded),
+    /// i.e. what is hoisted to the enclosing function or program.
+    /// The parser collects these itself, this is for ASTs made by builders.
+    pub fn hoisted_declarations(&self) -> (HashSet<Identifier>, Vec<FunctionDeclaration>) {
+        let mut variables = HashSet::new();
+        let mut functions = Vec::new();
+        for stmt in self.body.iter() {
+            stmt.collect_hoisted(&mut variables, &mut functions);
+        }
+        (variables, functions)
+    }
+}
+
 impl From<Vec<Statement>> for BlockStatement {
     fn from(body: Vec<Statement>) -> BlockStatement {
         BlockStatement {
@@ -158,6 +274,14 @@ pub struct IfStatement {
     pub alternate: Option<Statement>,
 }
 
+// ==============================================
+/// `with (object) body`: `object` properties become variables in `body`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WithStatement {
+    pub object: Expression,
+    pub body: Statement,
+}
+
 // ==============================================
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SwitchStatement {
@@ -187,6 +311,14 @@ pub struct ForInStatement {
     pub body: Statement,
 }
 
+/// `for (left of right) body`, `left` is a [`ForInTarget`] as well.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForOfStatement {
+    pub left: ForInTarget,
+    pub right: Expression,
+    pub body: Statement,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ForInTarget {
     Var(VariableDeclaration),
@@ -234,7 +366,7 @@ pub fn block(body: Vec<Statement>) -> BlockStatement {
 pub fn var<'a>(it: impl Iterator<Item = &'a (&'a str, Expression)>) -> VariableDeclaration {
     let declarations = it
         .map(|(name, init)| VariableDeclarator {
-            name: Identifier::from(*name),
+            name: Pattern::from(*name),
             init: Some(Box::new(init.clone())),
         })
         .collect();
@@ -253,3 +385,42 @@ pub fn expr(expr: Expression) -> Statement {
 pub fn return_(expr: Expression) -> Statement {
     Statement::from(ReturnStatement(Some(expr)))
 }
+
+/// make an [`IfStatement`] (JS: `if (test) consequent else alternate`)
+pub fn if_<E>(test: E, consequent: Statement, alternate: Option<Statement>) -> Statement
+where
+    Expression: From<E>,
+{
+    let test = Expression::from(test);
+    Statement::from(IfStatement {
+        test,
+        consequent,
+        alternate,
+    })
+}
+
+/// make a [`ForStatement`] (JS: `for (init; test; update) body`)
+pub fn for_(
+    init: Option<Statement>,
+    test: Option<Expression>,
+    update: Option<Expression>,
+    body: Statement,
+) -> Statement {
+    let init = init.unwrap_or(Statement::from(Stmt::Empty));
+    Statement::from(ForStatement {
+        init,
+        test,
+        update,
+        body,
+    })
+}
+
+/// make a `while` loop, which is a [`ForStatement`] without `init` and `update`
+/// (JS: `while (test) body`)
+pub fn while_<E>(test: E, body: Statement) -> Statement
+where
+    Expression: From<E>,
+{
+    let test = Expression::from(test);
+    for_(None, Some(test), None, body)
+}
@@ -0,0 +1,109 @@
+//! `AbortController` and `AbortSignal`: cancellation flags that scripts can poll
+//! and the host can raise with [`Heap::abort_signal`].
+
+use crate::error::TypeError;
+use crate::prelude::*;
+use crate::{
+    builtin::error, object::HostClass, CallContext, Exception, Heap, Interpreted, JSObject, JSRef,
+    JSResult, JSValue,
+};
+
+pub static CONTROLLER_CLASS: HostClass = HostClass {
+    name: "AbortController",
+    constructor: abort_controller_constructor,
+    methods: &[("abort", abort_controller_proto_abort)],
+    static_methods: &[],
+};
+
+pub static SIGNAL_CLASS: HostClass = HostClass {
+    name: "AbortSignal",
+    constructor: abort_signal_constructor,
+    methods: &[("throwIfAborted", abort_signal_proto_throwIfAborted)],
+    static_methods: &[],
+};
+
+fn abort_controller_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    if !heap.smells_fresh(call.this_ref) {
+        return Err(Exception::type_error(
+            TypeError::NEW_REQUIRED,
+            "AbortController",
+        ));
+    }
+
+    let signal_proto = (heap.get(Heap::GLOBAL).get_own_value("AbortSignal"))
+        .and_then(|ctor| ctor.to_ref().ok())
+        .and_then(|ctor_ref| heap.get(ctor_ref).get_own_value("prototype"))
+        .and_then(|proto| proto.to_ref().ok())
+        .unwrap_or(Heap::OBJECT_PROTO);
+    let mut signal = JSObject::new();
+    signal.proto = signal_proto;
+    signal.set_readonly("aborted", false)?;
+    signal.set_readonly("reason", JSValue::Undefined)?;
+    let signal_ref = heap.alloc(signal);
+
+    (heap.get_mut(call.this_ref)).set_readonly("signal", signal_ref)?;
+    Ok(Interpreted::VOID)
+}
+
+fn abort_controller_proto_abort(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    call.this_ref.expect_instance("AbortController", heap)?;
+    let signal_ref = (heap.get(call.this_ref).get_own_value("signal"))
+        .ok_or_else(|| Exception::instance_required(call.this_ref, "AbortController"))?
+        .to_ref()?;
+    let reason = call.arg_value(0, heap)?;
+    abort(signal_ref, reason, heap)?;
+    Ok(Interpreted::VOID)
+}
+
+/// `AbortSignal`s are only made by `AbortController`s.
+fn abort_signal_constructor(_call: CallContext, _heap: &mut Heap) -> JSResult<Interpreted> {
+    Err(Exception::type_error(
+        TypeError::ILLEGAL_CONSTRUCTOR,
+        "AbortSignal",
+    ))
+}
+
+#[allow(non_snake_case)]
+fn abort_signal_proto_throwIfAborted(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    call.this_ref.expect_instance("AbortSignal", heap)?;
+    let signal = heap.get(call.this_ref);
+    match signal.get_own_value("aborted") {
+        Some(JSValue::Bool(true)) => {
+            let reason = signal.get_own_value("reason").unwrap_or(JSValue::Undefined);
+            Err(Exception::UserThrown(reason))
+        }
+        _ => Ok(Interpreted::VOID),
+    }
+}
+
+/// Sets `aborted` and `reason` of the signal, once: aborting again does nothing.
+/// Without a `reason`, it is an `AbortError`.
+pub(crate) fn abort(signal_ref: JSRef, reason: JSValue, heap: &mut Heap) -> JSResult<()> {
+    signal_ref.expect_instance("AbortSignal", heap)?;
+    if let Some(JSValue::Bool(true)) = heap.get(signal_ref).get_own_value("aborted") {
+        return Ok(());
+    }
+
+    let reason = match reason {
+        JSValue::Undefined => {
+            let message = Interpreted::from("This operation was aborted");
+            let call = CallContext::from(vec![message]);
+            let error_ref = error::construct_error(Heap::ERROR_PROTO, call, heap)?.to_ref(heap)?;
+            heap.get_mut(error_ref).set_hidden("name", "AbortError")?;
+            JSValue::from(error_ref)
+        }
+        reason => reason,
+    };
+    let signal = heap.get_mut(signal_ref);
+    signal.set_even_nonwritable("aborted", true)?;
+    signal.set_even_nonwritable("reason", reason)?;
+    Ok(())
+}
+
+pub fn init(heap: &mut Heap) -> JSResult<()> {
+    let signal_proto = heap.alloc(JSObject::new());
+    heap.init_class(signal_proto, &SIGNAL_CLASS)?;
+    let controller_proto = heap.alloc(JSObject::new());
+    heap.init_class(controller_proto, &CONTROLLER_CLASS)?;
+    Ok(())
+}
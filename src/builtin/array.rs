@@ -1,38 +1,815 @@
-use crate::error::TypeError;
+use core::cmp::Ordering;
+
+use crate::error::{RangeError, TypeError};
 use crate::object::HostClass;
 use crate::prelude::*;
-use crate::{object::ObjectValue, CallContext, Exception, Heap, Interpreted, JSResult};
+use crate::{
+    object::ObjectValue, CallContext, Exception, Heap, Interpreted, JSObject, JSRef, JSResult,
+};
+
+/// `Array(...)` and `new Array(...)` behave the same:
+/// a single numeric argument is the length, otherwise the arguments are the elements.
+fn array_object_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let storage = match call.arguments.len() {
+        1 => match call.arg_value(0, heap)? {
+            JSValue::Number(n) => {
+                if n < 0.0 || n.trunc() != n || n > u32::MAX as f64 {
+                    return Err(Exception::range_error(RangeError::INVALID_LENGTH, n));
+                }
+                heap.check_array_length(n as usize)?;
+                vec![None; n as usize]
+            }
+            value => vec![Some(value)],
+        },
+        _ => (call.arguments.iter())
+            .map(|arg| arg.to_value(heap).map(Some))
+            .collect::<JSResult<Vec<Option<JSValue>>>>()?,
+    };
+    let array_ref = heap.alloc(JSObject::from_array_with_holes(storage));
+    Ok(Interpreted::from(array_ref))
+}
+
+/// `array.length = value`: elements from the new length on are removed, a longer length
+/// adds holes. The length must be a valid array index or a RangeError is thrown.
+pub(crate) fn set_length(array_ref: JSRef, value: JSValue, heap: &mut Heap) -> JSResult<()> {
+    let n = value.to_number(heap)?;
+    if !(0.0..=u32::MAX as f64).contains(&n) || n.trunc() != n {
+        return Err(Exception::range_error(RangeError::INVALID_LENGTH, n));
+    }
+    heap.check_array_length(n as usize)?;
+    if let Some(array) = heap.get_mut(array_ref).as_array_mut() {
+        array.check_removable(array_ref, n as usize..array.storage.len())?;
+        array.storage.resize(n as usize, None);
+    }
+    Ok(())
+}
+
+/// `Array.of(...items)`: the arguments are always the elements.
+fn array_object_of(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let storage = (call.arguments.iter())
+        .map(|arg| arg.to_value(heap))
+        .collect::<JSResult<Vec<JSValue>>>()?;
+    let array_ref = heap.alloc(JSObject::from_array(storage));
+    Ok(Interpreted::from(array_ref))
+}
+
+/// A relative index argument: negative ones count from `len`, the result is clamped to `0..=len`.
+fn relative_index(index: i64, len: usize) -> usize {
+    let len = len as i64;
+    let index = match index {
+        i if i < 0 => i64::max(i + len, 0),
+        i => i64::min(i, len),
+    };
+    index as usize
+}
+
+/// A relative index argument of `.copyWithin()`/`.fill()`, `None` if it is `undefined`.
+/// ToIntegerOrInfinity: `NaN` is `0`, infinities are clamped to `0..=len`.
+fn relative_index_arg(
+    call: &CallContext,
+    index: usize,
+    len: usize,
+    heap: &mut Heap,
+) -> JSResult<Option<usize>> {
+    match call.arg_value(index, heap)? {
+        JSValue::Undefined => Ok(None),
+        value => Ok(Some(relative_index(value.to_number(heap)? as i64, len))),
+    }
+}
+
+#[allow(non_snake_case)]
+fn array_proto_copyWithin(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let len = match heap.get(call.this_ref).as_array() {
+        Some(array) => array.storage.len(),
+        // TODO: generic object path
+        None => {
+            return Err(Exception::type_error(
+                TypeError::NOT_ARRAYLIKE,
+                call.this_ref,
+            ))
+        }
+    };
+    let target = relative_index_arg(&call, 0, len, heap)?.unwrap_or(0);
+    let start = relative_index_arg(&call, 1, len, heap)?.unwrap_or(0);
+    let end = relative_index_arg(&call, 2, len, heap)?.unwrap_or(len);
+
+    if let Some(array) = heap.get_mut(call.this_ref).as_array_mut() {
+        // `valueOf()` of the arguments might have shrunk the array
+        let len = usize::min(len, array.storage.len());
+        let (target, start) = (usize::min(target, len), usize::min(start, len));
+        let count = usize::min(end.saturating_sub(start), len - usize::max(target, start));
+        // holes are copied as holes
+        let copied = array.storage[start..start + count].to_vec();
+        array.storage[target..target + count].clone_from_slice(&copied);
+    }
+    Ok(Interpreted::from(call.this_ref))
+}
+
+/// `.fill(value, start, end)`: holes in the range are filled too.
+fn array_proto_fill(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let len = match heap.get(call.this_ref).as_array() {
+        Some(array) => array.storage.len(),
+        // TODO: generic object path
+        None => {
+            return Err(Exception::type_error(
+                TypeError::NOT_ARRAYLIKE,
+                call.this_ref,
+            ))
+        }
+    };
+    let value = call.arg_value(0, heap)?;
+    let start = relative_index_arg(&call, 1, len, heap)?.unwrap_or(0);
+    let end = relative_index_arg(&call, 2, len, heap)?.unwrap_or(len);
 
-fn array_object_constructor(_call: CallContext, _heap: &mut Heap) -> JSResult<Interpreted> {
-    todo!()
+    if let Some(array) = heap.get_mut(call.this_ref).as_array_mut() {
+        // `valueOf()` of the arguments might have shrunk the array
+        let end = usize::min(end, array.storage.len());
+        for item in array.storage[usize::min(start, end)..end].iter_mut() {
+            *item = Some(value.clone());
+        }
+    }
+    Ok(Interpreted::from(call.this_ref))
 }
 
+/// `.toString()` is `.join()`, if there is one.
 #[allow(non_snake_case)]
 fn array_toString(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
-    let array_object = heap.get(call.this_ref);
-    let array = array_object.as_array().ok_or_else(|| {
-        Exception::attr_type_error(TypeError::INSTANCE_REQUIRED, call.this_ref, "Array")
+    let join = match heap.lookup_protochain(call.this_ref, "join") {
+        Some(place) => place.to_value(heap)?,
+        None => JSValue::Undefined,
+    };
+    match join {
+        JSValue::Ref(funcref) if heap.get(funcref).is_callable() => heap.execute(
+            funcref,
+            CallContext::from(vec![])
+                .with_this(call.this_ref)
+                .with_name("join".into()),
+        ),
+        _ => super::object::object_proto_toString(call, heap),
+    }
+}
+
+/// Copies the elements of `this`: an array or an array-like object with a numeric `length`.
+/// Holes (missing indexed properties) are `None`.
+pub(crate) fn arraylike_storage(
+    this_ref: JSRef,
+    heap: &mut Heap,
+) -> JSResult<Vec<Option<JSValue>>> {
+    if let Some(array) = heap.get(this_ref).as_array() {
+        return Ok(array.storage.clone());
+    }
+
+    let len = arraylike_length(this_ref, heap)?;
+    (0..len).map(|i| element(this_ref, i, heap)).collect()
+}
+
+/// The `length` of an array or an array-like object.
+fn arraylike_length(this_ref: JSRef, heap: &mut Heap) -> JSResult<usize> {
+    if let Some(array) = heap.get(this_ref).as_array() {
+        return Ok(array.storage.len());
+    }
+
+    let length = Interpreted::member(this_ref, "length").to_value(heap)?;
+    let length = length.to_number(heap)?;
+    let len = if length.is_nan() || length <= 0.0 {
+        0
+    } else {
+        f64::min(length.trunc(), u32::MAX as f64) as usize
+    };
+    heap.check_array_length(len)?;
+    Ok(len)
+}
+
+/// The element `index` of an array or an array-like object as it is now, `None` for a hole.
+/// Indexed getters of array-likes are called.
+fn element(this_ref: JSRef, index: usize, heap: &mut Heap) -> JSResult<Option<JSValue>> {
+    let object = heap.get(this_ref);
+    if let Some(array) = object.as_array() {
+        return Ok(array.get(index).cloned());
+    }
+    let name = index.to_string();
+    if object.lookup_content(&name, heap).is_none() {
+        return Ok(None);
+    }
+    let value = Interpreted::member(this_ref, &name).to_value(heap)?;
+    Ok(Some(value))
+}
+
+/// The first of `indices` for which `found(element)` is true; holes are `None`.
+/// Arrays are searched in place, array-likes are read through [`element`].
+fn find_element<I, F>(
+    this_ref: JSRef,
+    mut indices: I,
+    heap: &mut Heap,
+    found: F,
+) -> JSResult<Option<usize>>
+where
+    I: Iterator<Item = usize>,
+    F: Fn(Option<&JSValue>) -> bool,
+{
+    if let Some(array) = heap.get(this_ref).as_array() {
+        return Ok(indices.find(|&i| found(array.get(i))));
+    }
+    for index in indices {
+        if found(element(this_ref, index, heap)?.as_ref()) {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
+
+/// `fromIndex` of `.indexOf()`/`.lastIndexOf()`/`.includes()`, `None` if it is not given.
+/// ToIntegerOrInfinity: `NaN` is `0`, infinities saturate.
+fn from_index_arg(call: &CallContext, index: usize, heap: &mut Heap) -> JSResult<Option<i64>> {
+    if index >= call.arguments.len() {
+        return Ok(None);
+    }
+    let from = call.arg_value(index, heap)?.to_number(heap)?;
+    Ok(Some(from as i64))
+}
+
+#[allow(non_snake_case)]
+fn array_proto_indexOf(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let search = call.arg_value(0, heap)?;
+    let len = arraylike_length(call.this_ref, heap)?;
+    let from = from_index_arg(&call, 1, heap)?.unwrap_or(0);
+
+    let start = relative_index(from, len);
+    // strict equality: `NaN` is never found, holes are skipped
+    let found = find_element(call.this_ref, start..len, heap, |value| {
+        value == Some(&search)
     })?;
-    let array = array.storage.clone();
+    Ok(Interpreted::from(found.map_or(-1, |i| i as i64)))
+}
+
+#[allow(non_snake_case)]
+fn array_proto_lastIndexOf(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let search = call.arg_value(0, heap)?;
+    let len = arraylike_length(call.this_ref, heap)? as i64;
+    let from = from_index_arg(&call, 1, heap)?;
+
+    let last = match from.unwrap_or(len - 1) {
+        i if i < 0 => i + len,
+        i => i64::min(i, len - 1),
+    };
+    let indices = (0..=last).rev().map(|i| i as usize);
+    let found = find_element(call.this_ref, indices, heap, |value| value == Some(&search))?;
+    Ok(Interpreted::from(found.map_or(-1, |i| i as i64)))
+}
+
+fn array_proto_includes(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let search = call.arg_value(0, heap)?;
+    if let JSValue::Number(n) = search {
+        if heap.get(call.this_ref).as_array().is_some() {
+            return array_includes_number(call, n, heap);
+        }
+    }
+    let len = arraylike_length(call.this_ref, heap)?;
+    let from = from_index_arg(&call, 1, heap)?.unwrap_or(0);
+
+    let start = relative_index(from, len);
+    // SameValueZero: `NaN` is found, holes are `undefined`
+    let found = find_element(call.this_ref, start..len, heap, |value| {
+        let value = value.unwrap_or(&JSValue::Undefined);
+        value.same_value_zero(&search)
+    })?;
+    Ok(Interpreted::from(found.is_some()))
+}
+
+/// The fast path of `.includes(n)` on arrays: elements are compared in place,
+/// without copying the storage.
+fn array_includes_number(call: CallContext, n: JSNumber, heap: &mut Heap) -> JSResult<Interpreted> {
+    let len = arraylike_length(call.this_ref, heap)?;
+    let from = from_index_arg(&call, 1, heap)?.unwrap_or(0);
+    let start = relative_index(from, len);
+
+    // `valueOf()` of `fromIndex` might have changed the array: only the first `len` count
+    let elements = (heap.get(call.this_ref).as_array().into_iter())
+        .flat_map(|array| array.storage.iter().take(len).skip(start));
+    let mut numbers = elements.filter_map(|item| match item {
+        Some(JSValue::Number(m)) => Some(*m),
+        _ => None,
+    });
+    // SameValueZero: `NaN` is found, `+0` and `-0` are equal
+    let found = match n.is_nan() {
+        true => numbers.any(|m| m.is_nan()),
+        false => numbers.any(|m| m == n),
+    };
+    Ok(Interpreted::from(found))
+}
+
+/// An array that contains itself (maybe indirectly) is joined as `""` where it recurs.
+fn array_proto_join(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    if heap.joining.contains(&call.this_ref) {
+        return Ok(Interpreted::from(""));
+    }
+    heap.joining.push(call.this_ref);
+    let result = join_elements(call, heap);
+    heap.joining.pop();
+    result
+}
+
+fn join_elements(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let len = arraylike_length(call.this_ref, heap)?;
+
+    let separator = match call.arg_value(0, heap)? {
+        JSValue::Undefined => JSString::from(","),
+        sep => sep.stringify(heap)?,
+    };
 
     let mut s = String::new();
-    for val in array.iter() {
-        if !s.is_empty() {
-            s += ",";
+    for index in 0..len {
+        if index > 0 {
+            s += separator.as_str();
+        }
+        // elements are read as they are now: `toString()` of earlier ones may change them
+        let value = element(call.this_ref, index, heap)?.unwrap_or(JSValue::Undefined);
+        // holes, `undefined` and `null` are empty
+        if value != JSValue::Undefined && value != JSValue::NULL {
+            s += value.stringify(heap)?.as_ref();
         }
-        s += val.stringify(heap)?.as_ref();
     }
     Ok(Interpreted::from(s))
 }
 
+/// Checks that `comparefn` of `.sort()`/`.toSorted()` is either `undefined` or callable.
+fn sort_comparator(call: &CallContext, heap: &mut Heap) -> JSResult<Option<JSRef>> {
+    match call.arg_value(0, heap)? {
+        JSValue::Undefined => Ok(None),
+        JSValue::Ref(funcref) if heap.get(funcref).is_callable() => Ok(Some(funcref)),
+        value => Err(Exception::type_error(TypeError::NOT_CALLABLE, value)),
+    }
+}
+
+/// SortCompare: `undefined`s must be filtered out before.
+fn sort_compare(
+    a: &JSValue,
+    b: &JSValue,
+    cmp: Option<JSRef>,
+    heap: &mut Heap,
+) -> JSResult<Ordering> {
+    match cmp {
+        Some(funcref) => {
+            let arguments = vec![Interpreted::from(a.clone()), Interpreted::from(b.clone())];
+            let call = CallContext::from(arguments).with_this(Heap::GLOBAL);
+            let result = heap.execute(funcref, call)?.to_value(heap)?;
+            let result = result.to_number(heap)?;
+            // `NaN` is treated as `0`
+            Ok(result.partial_cmp(&0.0).unwrap_or(Ordering::Equal))
+        }
+        None => {
+            let a = a.stringify(heap)?;
+            let b = b.stringify(heap)?;
+            Ok(a.as_str().cmp(b.as_str()))
+        }
+    }
+}
+
+/// A stable merge sort that tolerates inconsistent comparators and propagates their exceptions.
+fn merge_sort(values: Vec<JSValue>, cmp: Option<JSRef>, heap: &mut Heap) -> JSResult<Vec<JSValue>> {
+    if values.len() <= 1 {
+        return Ok(values);
+    }
+    let mut left = values;
+    let right = left.split_off(left.len() / 2);
+    let left = merge_sort(left, cmp, heap)?;
+    let right = merge_sort(right, cmp, heap)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        // take from the right only if strictly less: this keeps the sort stable
+        let next = if sort_compare(r, l, cmp, heap)? == Ordering::Less {
+            right.next()
+        } else {
+            left.next()
+        };
+        merged.extend(next);
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+/// Sorts `storage`: `undefined`s go after the sorted values, holes go last.
+fn sort_storage(
+    storage: Vec<Option<JSValue>>,
+    cmp: Option<JSRef>,
+    heap: &mut Heap,
+) -> JSResult<Vec<Option<JSValue>>> {
+    let len = storage.len();
+    let values = (storage.into_iter().flatten()).collect::<Vec<_>>();
+    let holes = len - values.len();
+    let (undefineds, values): (Vec<_>, Vec<_>) =
+        (values.into_iter()).partition(|value| value == &JSValue::Undefined);
+
+    let mut sorted = merge_sort(values, cmp, heap)?
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<_>>();
+    sorted.extend(undefineds.into_iter().map(Some));
+    sorted.extend(core::iter::repeat_n(None, holes));
+    Ok(sorted)
+}
+
+fn array_proto_sort(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let cmp = sort_comparator(&call, heap)?;
+    let storage = match heap.get(call.this_ref).as_array() {
+        Some(array) => array.storage.clone(),
+        // TODO: generic object path
+        None => {
+            return Err(Exception::type_error(
+                TypeError::NOT_ARRAYLIKE,
+                call.this_ref,
+            ))
+        }
+    };
+    let sorted = sort_storage(storage, cmp, heap)?;
+    if let Some(array) = heap.get_mut(call.this_ref).as_array_mut() {
+        array.storage = sorted;
+    }
+    Ok(Interpreted::from(call.this_ref))
+}
+
+#[allow(non_snake_case)]
+fn array_proto_toSorted(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let cmp = sort_comparator(&call, heap)?;
+    let storage = arraylike_storage(call.this_ref, heap)?;
+    // holes become `undefined`
+    let storage = storage
+        .into_iter()
+        .map(|value| Some(value.unwrap_or(JSValue::Undefined)));
+    let sorted = sort_storage(storage.collect(), cmp, heap)?;
+    let array_ref = heap.alloc(JSObject::from_array_with_holes(sorted));
+    Ok(Interpreted::from(array_ref))
+}
+
+fn array_proto_reverse(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    match heap.get_mut(call.this_ref).as_array_mut() {
+        // holes stay holes
+        Some(array) => array.storage.reverse(),
+        // TODO: generic object path
+        None => {
+            return Err(Exception::type_error(
+                TypeError::NOT_ARRAYLIKE,
+                call.this_ref,
+            ))
+        }
+    }
+    Ok(Interpreted::from(call.this_ref))
+}
+
+#[allow(non_snake_case)]
+fn array_proto_toReversed(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let storage = arraylike_storage(call.this_ref, heap)?;
+    // holes become `undefined`
+    let values = (storage.into_iter().rev())
+        .map(|value| value.unwrap_or(JSValue::Undefined))
+        .collect();
+    let array_ref = heap.alloc(JSObject::from_array(values));
+    Ok(Interpreted::from(array_ref))
+}
+
+/// `.with(index, value)` is a copy of the array with one element replaced.
+fn array_proto_with(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let index = call.arg_as_number(0, heap)?.unwrap_or(0);
+    let value = call.arg_value(1, heap)?;
+    let storage = arraylike_storage(call.this_ref, heap)?;
+
+    let len = storage.len() as i64;
+    let actual = if index < 0 { index + len } else { index };
+    if !(0..len).contains(&actual) {
+        return Err(Exception::range_error(
+            RangeError::INVALID_INDEX,
+            index as f64,
+        ));
+    }
+
+    // holes become `undefined`
+    let mut values = (storage.into_iter())
+        .map(|value| value.unwrap_or(JSValue::Undefined))
+        .collect::<Vec<_>>();
+    values[actual as usize] = value;
+    let array_ref = heap.alloc(JSObject::from_array(values));
+    Ok(Interpreted::from(array_ref))
+}
+
+/// `.flat(depth = 1)`: nested arrays are spliced in up to `depth` levels, holes are skipped.
+/// Uses a worklist instead of recursion, so `.flat(Infinity)` does not overflow the stack.
+fn array_proto_flat(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let depth = match call.arg_value(0, heap)? {
+        JSValue::Undefined => 1.0,
+        depth => depth.to_number(heap)?,
+    };
+    // ToIntegerOrInfinity, negative depths flatten nothing
+    let depth = if depth.is_nan() || depth < 0.0 {
+        0.0
+    } else {
+        depth.trunc()
+    };
+
+    let storage = arraylike_storage(call.this_ref, heap)?;
+    let mut worklist = vec![(storage.into_iter(), depth)];
+    let mut values = vec![];
+    while let Some((elements, depth)) = worklist.last_mut() {
+        let depth = *depth;
+        let value = match elements.next() {
+            Some(Some(value)) => value,
+            Some(None) => continue,
+            None => {
+                worklist.pop();
+                continue;
+            }
+        };
+        if let JSValue::Ref(nested) = &value {
+            match heap.get(*nested).as_array() {
+                Some(array) if depth >= 1.0 => {
+                    let storage = array.storage.clone();
+                    worklist.push((storage.into_iter(), depth - 1.0));
+                    continue;
+                }
+                _ => (),
+            }
+        }
+        values.push(value);
+        heap.check_array_length(values.len())?;
+    }
+    let array_ref = heap.alloc(JSObject::from_array(values));
+    Ok(Interpreted::from(array_ref))
+}
+
+/// The callback of `.forEach()`, `.map()`, `.filter()` and its `this`, the second argument.
+fn callback_args(call: &CallContext, heap: &mut Heap) -> JSResult<(JSRef, JSRef)> {
+    let callback = call.arg_value(0, heap)?;
+    let func_ref = match callback {
+        JSValue::Ref(r) if heap.get(r).is_callable() => r,
+        _ => return Err(Exception::type_error(TypeError::NOT_CALLABLE, callback)),
+    };
+    let this_ref = match call.arg_value(1, heap)? {
+        JSValue::Undefined => Heap::GLOBAL,
+        this_arg => this_arg.objectify(heap),
+    };
+    Ok((func_ref, this_ref))
+}
+
+/// Calls `callback(value, index, array)` for every element of `this` up to `len`,
+/// its initial length. Elements are read as the callback goes, holes are skipped.
+/// There is no `break`: an exception from the callback stops the iteration and propagates.
+fn for_each_element<F>(call: &CallContext, len: usize, heap: &mut Heap, mut each: F) -> JSResult<()>
+where
+    F: FnMut(usize, JSValue, JSValue),
+{
+    let (func_ref, this_ref) = callback_args(call, heap)?;
+    for index in 0..len {
+        let value = match element(call.this_ref, index, heap)? {
+            Some(value) => value,
+            None => continue,
+        };
+        let arguments = vec![
+            Interpreted::from(value.clone()),
+            Interpreted::from(index as f64),
+            Interpreted::from(call.this_ref),
+        ];
+        let callback_call = CallContext::from(arguments).with_this(this_ref);
+        let result = heap.execute(func_ref, callback_call)?.to_value(heap)?;
+        each(index, value, result);
+    }
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+fn array_proto_forEach(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let len = arraylike_length(call.this_ref, heap)?;
+    for_each_element(&call, len, heap, |_, _, _| ())?;
+    Ok(Interpreted::VOID)
+}
+
+/// `.map()` keeps holes where `this` has them.
+fn array_proto_map(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let len = arraylike_length(call.this_ref, heap)?;
+    let mut storage = vec![None; len];
+    for_each_element(&call, len, heap, |index, _, result| {
+        storage[index] = Some(result)
+    })?;
+    let array_ref = heap.alloc(JSObject::from_array_with_holes(storage));
+    Ok(Interpreted::from(array_ref))
+}
+
+fn array_proto_filter(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let mut values = vec![];
+    let mut kept = vec![];
+    let len = arraylike_length(call.this_ref, heap)?;
+    for_each_element(&call, len, heap, |_, value, result| {
+        values.push(value);
+        kept.push(result);
+    })?;
+    let values = (values.into_iter().zip(kept))
+        .filter(|(_, result)| result.boolify(heap))
+        .map(|(value, _)| value)
+        .collect();
+    let array_ref = heap.alloc(JSObject::from_array(values));
+    Ok(Interpreted::from(array_ref))
+}
+
+/// `.reduce(callback, initial)` skips holes: they are never passed to `callback`.
+/// Without `initial`, the first element is the initial accumulator, and it is a TypeError
+/// if there are no elements at all.
+fn array_proto_reduce(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let callback = call.arg_value(0, heap)?;
+    let func_ref = match callback {
+        JSValue::Ref(r) if heap.get(r).is_callable() => r,
+        _ => return Err(Exception::type_error(TypeError::NOT_CALLABLE, callback)),
+    };
+    let len = arraylike_length(call.this_ref, heap)?;
+
+    let mut indices = 0..len;
+    let mut accumulator = match call.arguments.len() {
+        0 | 1 => {
+            let mut first = None;
+            for index in indices.by_ref() {
+                first = element(call.this_ref, index, heap)?;
+                if first.is_some() {
+                    break;
+                }
+            }
+            first.ok_or_else(|| Exception::type_error(TypeError::REDUCE_EMPTY, call.this_ref))?
+        }
+        _ => call.arg_value(1, heap)?,
+    };
+    for index in indices {
+        let value = match element(call.this_ref, index, heap)? {
+            Some(value) => value,
+            None => continue,
+        };
+        let arguments = vec![
+            Interpreted::from(accumulator),
+            Interpreted::from(value),
+            Interpreted::from(index as f64),
+            Interpreted::from(call.this_ref),
+        ];
+        let callback_call = CallContext::from(arguments).with_this(Heap::GLOBAL);
+        accumulator = heap.execute(func_ref, callback_call)?.to_value(heap)?;
+    }
+    Ok(Interpreted::from(accumulator))
+}
+
+/// `.concat(...items)`: `this` and the arguments are spread if they are arrays,
+/// anything else (array-likes too) becomes a single element.
+// TODO: `Symbol.isConcatSpreadable`, once there are symbols
+fn array_proto_concat(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let mut items = vec![JSValue::from(call.this_ref)];
+    for i in 0..call.arguments.len() {
+        items.push(call.arg_value(i, heap)?);
+    }
+
+    let mut storage = vec![];
+    for item in items {
+        let array = match &item {
+            JSValue::Ref(r) => heap.get(*r).as_array(),
+            _ => None,
+        };
+        match array {
+            // holes stay holes
+            Some(array) => storage.extend(array.storage.iter().cloned()),
+            None => storage.push(Some(item)),
+        }
+        heap.check_array_length(storage.len())?;
+    }
+    let array_ref = heap.alloc(JSObject::from_array_with_holes(storage));
+    Ok(Interpreted::from(array_ref))
+}
+
+/// The hidden prototype of array iterators, kept on `Array.prototype`.
+const ARRAY_ITERATOR_PROTO: &str = "[[ArrayIterator]]";
+
+/// Sets up the prototype of `.entries()`/`.keys()`/`.values()` iterators,
+/// `.values()` is also the iterator of arrays.
+pub fn init_iterators(heap: &mut Heap) -> JSResult<()> {
+    let next_ref = heap.alloc_func(array_iterator_next);
+    let mut iterator_proto = JSObject::new();
+    iterator_proto.set_hidden("next", next_ref)?;
+    let iterator_proto_ref = heap.alloc(iterator_proto);
+
+    let array_proto = heap.get_mut(Heap::ARRAY_PROTO);
+    array_proto.set_system(ARRAY_ITERATOR_PROTO, iterator_proto_ref)?;
+    let values = (array_proto.get_own_value("values")).expect("no Array.prototype.values");
+    array_proto.set_system(Heap::SYMBOL_ITERATOR, values)
+}
+
+/// Makes an iterator over `call.this_ref`, `kind` is "entries", "keys" or "values".
+fn make_array_iterator(call: CallContext, kind: &str, heap: &mut Heap) -> JSResult<Interpreted> {
+    let iterator_proto = (heap.get(Heap::ARRAY_PROTO))
+        .get_own_value(ARRAY_ITERATOR_PROTO)
+        .expect("no array iterator prototype")
+        .to_ref()?;
+
+    let mut iterator = JSObject::new();
+    iterator.proto = iterator_proto;
+    iterator.set_system("[[iterated]]", call.this_ref)?;
+    iterator.set_system("[[index]]", 0)?;
+    iterator.set_system("[[kind]]", kind)?;
+    let iterator_ref = heap.alloc(iterator);
+    Ok(Interpreted::from(iterator_ref))
+}
+
+fn array_proto_entries(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    make_array_iterator(call, "entries", heap)
+}
+
+fn array_proto_keys(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    make_array_iterator(call, "keys", heap)
+}
+
+fn array_proto_values(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    make_array_iterator(call, "values", heap)
+}
+
+/// `next()` of array iterators: `{value, done}`.
+fn array_iterator_next(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let iterator = heap.get(call.this_ref);
+    let (iterated, index, kind) = match (
+        iterator.get_own_value("[[iterated]]"),
+        iterator.get_own_value("[[index]]"),
+        iterator.get_own_value("[[kind]]"),
+    ) {
+        (Some(iterated), Some(JSValue::Number(index)), Some(JSValue::String(kind))) => {
+            (iterated, index as usize, kind)
+        }
+        _ => {
+            let what = "Array Iterator";
+            return Err(Exception::attr_type_error(
+                TypeError::INSTANCE_REQUIRED,
+                call.this_ref,
+                what,
+            ));
+        }
+    };
+
+    // a finished iterator forgets its array
+    let iterated_ref = match iterated {
+        JSValue::Ref(iterated_ref) => iterated_ref,
+        _ => return iterator_result(JSValue::Undefined, true, heap),
+    };
+    let len = match heap.get(iterated_ref).as_array() {
+        Some(array) => array.storage.len(),
+        None => {
+            let length = Interpreted::member(iterated_ref, "length").to_value(heap)?;
+            let length = length.to_number(heap)?;
+            if length.is_nan() || length <= 0.0 {
+                0
+            } else {
+                length as usize
+            }
+        }
+    };
+    if index >= len {
+        let iterator = heap.get_mut(call.this_ref);
+        iterator.set_even_nonwritable("[[iterated]]", JSValue::Undefined)?;
+        return iterator_result(JSValue::Undefined, true, heap);
+    }
+
+    (heap.get_mut(call.this_ref)).set_even_nonwritable("[[index]]", (index + 1) as f64)?;
+    let element = || Interpreted::member(iterated_ref, &index.to_string());
+    let value = match kind.as_str() {
+        "keys" => JSValue::from(index as f64),
+        "values" => element().to_value(heap)?,
+        _ => {
+            let element = element().to_value(heap)?;
+            let entry = JSObject::from_array(vec![JSValue::from(index as f64), element]);
+            JSValue::from(heap.alloc(entry))
+        }
+    };
+    iterator_result(value, false, heap)
+}
+
+/// Makes an iterator result object: `{value, done}`.
+pub(super) fn iterator_result(
+    value: JSValue,
+    done: bool,
+    heap: &mut Heap,
+) -> JSResult<Interpreted> {
+    let mut result = JSObject::new();
+    result.set_property("value", value)?;
+    result.set_property("done", done)?;
+    Ok(Interpreted::from(heap.alloc(result)))
+}
+
 fn array_proto_push(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
     let arguments = (call.arguments.into_iter())
         .map(|arg| arg.to_value(heap))
         .collect::<JSResult<Vec<JSValue>>>()?;
+    if let Some(array) = heap.get(call.this_ref).as_array() {
+        heap.check_array_length(array.storage.len() + arguments.len())?;
+    }
     let array_object = heap.get_mut(call.this_ref);
+    if !array_object.is_extensible() && !arguments.is_empty() {
+        let what = Interpreted::from(call.this_ref);
+        return Err(Exception::type_error(TypeError::NOT_EXTENSIBLE, what));
+    }
     match &mut array_object.value {
         ObjectValue::Array(array) => {
-            array.storage.extend(arguments);
+            array.storage.extend(arguments.into_iter().map(Some));
             let length = array_object
                 .get_own_value("length")
                 .unwrap_or_else(|| JSValue::from(0));
@@ -50,7 +827,9 @@ fn array_proto_pop(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted>
     let array_object = heap.get_mut(call.this_ref);
     match &mut array_object.value {
         ObjectValue::Array(array) => {
-            let value = array.storage.pop().unwrap_or(JSValue::Undefined);
+            let len = array.storage.len();
+            array.check_removable(call.this_ref, len.saturating_sub(1)..len)?;
+            let value = array.storage.pop().flatten().unwrap_or(JSValue::Undefined);
             Ok(Interpreted::from(value))
         }
         // TODO: generic object path
@@ -61,13 +840,83 @@ fn array_proto_pop(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted>
     }
 }
 
+fn array_proto_shift(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let array_object = heap.get_mut(call.this_ref);
+    match &mut array_object.value {
+        ObjectValue::Array(array) => {
+            // every element moves down, the last index is removed
+            array.check_removable(call.this_ref, 0..array.storage.len())?;
+            let value = match array.storage.is_empty() {
+                true => None,
+                false => array.storage.remove(0),
+            };
+            Ok(Interpreted::from(value.unwrap_or(JSValue::Undefined)))
+        }
+        // TODO: generic object path
+        _ => Err(Exception::type_error(
+            TypeError::NOT_ARRAYLIKE,
+            call.this_ref,
+        )),
+    }
+}
+
+fn array_proto_unshift(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let arguments = (call.arguments.into_iter())
+        .map(|arg| arg.to_value(heap))
+        .collect::<JSResult<Vec<JSValue>>>()?;
+    if let Some(array) = heap.get(call.this_ref).as_array() {
+        heap.check_array_length(array.storage.len() + arguments.len())?;
+    }
+    let array_object = heap.get_mut(call.this_ref);
+    if !array_object.is_extensible() && !arguments.is_empty() {
+        let what = Interpreted::from(call.this_ref);
+        return Err(Exception::type_error(TypeError::NOT_EXTENSIBLE, what));
+    }
+    match &mut array_object.value {
+        ObjectValue::Array(array) => {
+            array.storage.splice(0..0, arguments.into_iter().map(Some));
+            let length = array.storage.len() as i64;
+            Ok(Interpreted::from(length))
+        }
+        // TODO: generic object path
+        _ => Err(Exception::type_error(
+            TypeError::NOT_ARRAYLIKE,
+            call.this_ref,
+        )),
+    }
+}
+
 pub static CLASS: HostClass = HostClass {
     name: "Array",
     constructor: array_object_constructor,
     methods: &[
+        ("concat", array_proto_concat),
+        ("copyWithin", array_proto_copyWithin),
+        ("entries", array_proto_entries),
+        ("fill", array_proto_fill),
+        ("filter", array_proto_filter),
+        ("flat", array_proto_flat),
+        ("forEach", array_proto_forEach),
+        ("includes", array_proto_includes),
+        ("indexOf", array_proto_indexOf),
+        ("join", array_proto_join),
+        ("keys", array_proto_keys),
+        ("lastIndexOf", array_proto_lastIndexOf),
+        ("map", array_proto_map),
         ("pop", array_proto_pop),
         ("push", array_proto_push),
+        ("reduce", array_proto_reduce),
+        ("reverse", array_proto_reverse),
+        ("shift", array_proto_shift),
+        ("sort", array_proto_sort),
+        // there is no `Intl`, locales are ignored
+        ("toLocaleString", array_toString),
+        ("toReversed", array_proto_toReversed),
+        ("toSorted", array_proto_toSorted),
         ("toString", array_toString),
+        ("unshift", array_proto_unshift),
+        ("values", array_proto_values),
+        ("with", array_proto_with),
     ],
-    static_methods: &[],
+    static_methods: &[("of", array_object_of)],
 };
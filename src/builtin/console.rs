@@ -0,0 +1,214 @@
+//! The builtin `console` object.
+
+use crate::prelude::*;
+use crate::{CallContext, Heap, Interpreted, JSObject, JSResult};
+
+static METHODS: &[(&str, crate::HostFn)] = &[
+    ("count", console_count),
+    ("countReset", console_countReset),
+    ("debug", console_log),
+    ("error", console_error),
+    ("group", console_group),
+    ("groupCollapsed", console_group),
+    ("groupEnd", console_groupEnd),
+    ("info", console_log),
+    ("log", console_log),
+    ("table", console_table),
+    ("warn", console_error),
+];
+
+/// The state of `console.group()` and `console.count()`, kept on the heap.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConsoleState {
+    /// how many `console.group()`s are open
+    group_depth: usize,
+    /// `console.count()` counters by label
+    counts: HashMap<JSString, u64>,
+}
+
+/// Formats arguments of `console.log` and alike.
+fn format_arguments(call: &CallContext, heap: &mut Heap) -> JSResult<String> {
+    let mut items = vec![];
+    for i in 0..call.arguments.len() {
+        let value = call.arg_value(i, heap)?;
+        items.push(heap.inspect(&value));
+    }
+    Ok(items.join(" "))
+}
+
+/// Indents every line of `text` by two spaces for each open `console.group()`.
+fn indent(text: &str, heap: &Heap) -> String {
+    let indent = "  ".repeat(heap.console.group_depth);
+    let lines = text.split('\n').map(|line| format!("{}{}", indent, line));
+    lines.collect::<Vec<_>>().join("\n")
+}
+
+fn print(text: &str, heap: &mut Heap) {
+    let text = indent(text, heap);
+    heap.print(&text);
+}
+
+fn eprint(text: &str, heap: &mut Heap) {
+    let text = indent(text, heap);
+    heap.eprint(&text);
+}
+
+fn console_log(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let line = format_arguments(&call, heap)?;
+    print(&line, heap);
+    Ok(Interpreted::VOID)
+}
+
+fn console_error(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let line = format_arguments(&call, heap)?;
+    eprint(&line, heap);
+    Ok(Interpreted::VOID)
+}
+
+/// `console.group(...label)`: prints the label (if any) and indents the output after it.
+fn console_group(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    if !call.arguments.is_empty() {
+        let line = format_arguments(&call, heap)?;
+        print(&line, heap);
+    }
+    heap.console.group_depth += 1;
+    Ok(Interpreted::VOID)
+}
+
+#[allow(non_snake_case)]
+fn console_groupEnd(_call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    heap.console.group_depth = heap.console.group_depth.saturating_sub(1);
+    Ok(Interpreted::VOID)
+}
+
+/// The label of `console.count()`/`console.countReset()`, `"default"` if `undefined`.
+fn count_label(call: &CallContext, heap: &mut Heap) -> JSResult<JSString> {
+    match call.arg_value(0, heap)? {
+        JSValue::Undefined => Ok(JSString::from("default")),
+        label => label.stringify(heap),
+    }
+}
+
+/// `console.count(label)`: prints how many times it has been called with `label`.
+fn console_count(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let label = count_label(&call, heap)?;
+    let count = heap.console.counts.entry(label.clone()).or_insert(0);
+    *count += 1;
+    let line = format!("{}: {}", label, count);
+    print(&line, heap);
+    Ok(Interpreted::VOID)
+}
+
+#[allow(non_snake_case)]
+fn console_countReset(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let label = count_label(&call, heap)?;
+    match heap.console.counts.get_mut(&label) {
+        Some(count) => *count = 0,
+        None => eprint(&format!("Count for '{}' does not exist", label), heap),
+    }
+    Ok(Interpreted::VOID)
+}
+
+/// `console.table(data, columns)`: a row for every enumerable property of `data`,
+/// a column for every property of the rows (or `Values` for primitive rows).
+/// `columns` (an array of property names) chooses the columns to show.
+/// A primitive `data` is just logged.
+fn console_table(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let data_ref = match call.arg_value(0, heap)? {
+        JSValue::Ref(data_ref) if data_ref != Heap::NULL => data_ref,
+        _ => return console_log(call, heap),
+    };
+    let chosen = match call.arg_value(1, heap)? {
+        JSValue::Ref(columns_ref) if heap.get(columns_ref).as_array().is_some() => {
+            let names = super::array::arraylike_storage(columns_ref, heap)?;
+            let names = names.into_iter().flatten().map(|name| name.stringify(heap));
+            Some(names.collect::<JSResult<Vec<_>>>()?)
+        }
+        _ => None,
+    };
+
+    let mut columns: Vec<JSString> = chosen.clone().unwrap_or_default();
+    let mut rows = vec![];
+    for index in heap.get(data_ref).enumerable_keys() {
+        let row = Interpreted::member(data_ref, &index).to_value(heap)?;
+        let mut cells = vec![];
+        let mut value = None;
+        match row {
+            JSValue::Ref(row_ref) if row_ref != Heap::NULL => {
+                for column in heap.get(row_ref).enumerable_keys() {
+                    if let Some(chosen) = &chosen {
+                        if !chosen.contains(&column) {
+                            continue;
+                        }
+                    } else if !columns.contains(&column) {
+                        columns.push(column.clone());
+                    }
+                    let cell = Interpreted::member(row_ref, &column).to_value(heap)?;
+                    cells.push((column, heap.inspect_quoted(&cell)));
+                }
+            }
+            primitive => value = Some(heap.inspect_quoted(&primitive)),
+        }
+        rows.push((index, cells, value));
+    }
+    let has_values = rows.iter().any(|(_, _, value)| value.is_some());
+
+    let mut header = vec![String::from("(index)")];
+    header.extend(columns.iter().map(|column| column.to_string()));
+    if has_values {
+        header.push(String::from("Values"));
+    }
+    let mut lines = vec![header];
+    for (index, cells, value) in rows {
+        let mut line = vec![index.to_string()];
+        for column in columns.iter() {
+            let cell = cells.iter().find(|(name, _)| name == column);
+            line.push(cell.map(|(_, cell)| cell.clone()).unwrap_or_default());
+        }
+        if has_values {
+            line.push(value.unwrap_or_default());
+        }
+        lines.push(line);
+    }
+
+    let text = render_table(&lines);
+    print(&text, heap);
+    Ok(Interpreted::VOID)
+}
+
+/// Draws `lines` (the first one is the header) as a table with box-drawing borders.
+fn render_table(lines: &[Vec<String>]) -> String {
+    let widths = (0..lines[0].len())
+        .map(|i| (lines.iter()).map(|line| line[i].chars().count()).max())
+        .map(|width| width.unwrap_or(0))
+        .collect::<Vec<usize>>();
+    let border = |left: &str, middle: &str, right: &str| {
+        let dashes = widths.iter().map(|&width| "─".repeat(width + 2));
+        let dashes = dashes.collect::<Vec<_>>().join(middle);
+        format!("{}{}{}", left, dashes, right)
+    };
+    let row = |line: &Vec<String>| {
+        let cells = (line.iter().zip(widths.iter()))
+            .map(|(cell, &width)| format!(" {:<width$} ", cell, width = width));
+        format!("│{}│", cells.collect::<Vec<_>>().join("│"))
+    };
+
+    let mut output = vec![border("┌", "┬", "┐"), row(&lines[0])];
+    output.push(border("├", "┼", "┤"));
+    output.extend(lines[1..].iter().map(row));
+    output.push(border("└", "┴", "┘"));
+    output.join("\n")
+}
+
+pub fn init(heap: &mut Heap) -> JSResult<()> {
+    let mut console_object = JSObject::new();
+    for &(name, func) in METHODS.iter() {
+        let func = heap.alloc_func(func);
+        console_object.set_hidden(name, func)?;
+    }
+
+    let console_ref = heap.alloc(console_object);
+    heap.get_mut(Heap::GLOBAL)
+        .set_hidden("console", console_ref)?;
+    Ok(())
+}
@@ -1,5 +1,8 @@
 use crate::{
-    function::CallContext, object::HostClass, prelude::*, Heap, Interpreted, JSObject, JSResult,
+    function::{CallContext, HostFn},
+    object::HostClass,
+    prelude::*,
+    Heap, Interpreted, JSObject, JSRef, JSResult,
 };
 
 pub static CLASS: HostClass = HostClass {
@@ -9,34 +12,57 @@ pub static CLASS: HostClass = HostClass {
     static_methods: &[],
 };
 
+/// `Error` subclasses: they only differ by their prototypes.
+static SUBCLASSES: &[(&str, HostFn)] = &[
+    ("EvalError", eval_error_constructor),
+    ("RangeError", range_error_constructor),
+    ("ReferenceError", reference_error_constructor),
+    ("SyntaxError", syntax_error_constructor),
+    ("TypeError", type_error_constructor),
+    ("URIError", uri_error_constructor),
+];
+
 pub fn error_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    construct_error(Heap::ERROR_PROTO, call, heap)
+}
+
+/// Makes an error object with `proto` and the message from `call.arguments`.
+/// The optional second argument is `{ cause }`.
+pub fn construct_error(proto: JSRef, call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
     let message = (call.arguments.first())
         .unwrap_or(&Interpreted::from(""))
         .to_value(heap)?
         .stringify(heap)?;
 
     let mut error_object = JSObject::new();
-    error_object.proto = Heap::ERROR_PROTO;
+    error_object.proto = proto;
 
     error_object.set_hidden("message", message)?;
 
+    // `new Error(message, { cause })`
+    if let JSValue::Ref(options_ref) = call.arg_value(1, heap)? {
+        let options = heap.get(options_ref);
+        if options.lookup_value("cause", heap).is_some() {
+            let cause = Interpreted::member(options_ref, "cause").to_value(heap)?;
+            error_object.set_hidden("cause", cause)?;
+        }
+    }
+
     let objref = heap.alloc(error_object);
     Ok(Interpreted::from(objref))
 }
 
 #[allow(non_snake_case)]
 fn error_proto_toString(call: CallContext, heap: &'_ mut Heap) -> JSResult<Interpreted> {
-    call.this_ref.expect_instance("Error", heap)?;
-
-    let name = (heap.get(call.this_ref))
-        .lookup_value("name", heap)
-        .unwrap_or_else(|| JSValue::from(""))
-        .stringify(heap)?;
-
-    let message = (heap.get(call.this_ref))
-        .lookup_value("message", heap)
-        .unwrap_or_else(|| JSValue::from(""))
-        .stringify(heap)?;
+    // Generic: any object with `name`/`message` (possibly inherited or getters) will do.
+    let name = match Interpreted::member(call.this_ref, "name").to_value(heap)? {
+        JSValue::Undefined => JSString::from("Error"),
+        value => value.stringify(heap)?,
+    };
+    let message = match Interpreted::member(call.this_ref, "message").to_value(heap)? {
+        JSValue::Undefined => JSString::from(""),
+        value => value.stringify(heap)?,
+    };
 
     Ok(Interpreted::from(match () {
         _ if message.is_empty() => name,
@@ -44,3 +70,56 @@ fn error_proto_toString(call: CallContext, heap: &'_ mut Heap) -> JSResult<Inter
         _ => JSString::from(name.to_string() + ": " + &message),
     }))
 }
+
+/// Finds `<name>.prototype` of a global `Error` subclass, falls back to `Error.prototype`.
+pub fn subclass_proto(name: &str, heap: &Heap) -> JSRef {
+    (heap.get(Heap::GLOBAL).get_own_value(name))
+        .and_then(|ctor| ctor.to_ref().ok())
+        .and_then(|ctor_ref| heap.get(ctor_ref).get_own_value("prototype"))
+        .and_then(|proto| proto.to_ref().ok())
+        .unwrap_or(Heap::ERROR_PROTO)
+}
+
+fn eval_error_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    construct_error(subclass_proto("EvalError", heap), call, heap)
+}
+
+fn range_error_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    construct_error(subclass_proto("RangeError", heap), call, heap)
+}
+
+fn reference_error_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    construct_error(subclass_proto("ReferenceError", heap), call, heap)
+}
+
+fn syntax_error_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    construct_error(subclass_proto("SyntaxError", heap), call, heap)
+}
+
+fn type_error_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    construct_error(subclass_proto("TypeError", heap), call, heap)
+}
+
+fn uri_error_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    construct_error(subclass_proto("URIError", heap), call, heap)
+}
+
+/// Makes `EvalError`, `RangeError`, `TypeError`, etc.
+pub fn init_subclasses(heap: &mut Heap) -> JSResult<()> {
+    for &(name, constructor) in SUBCLASSES.iter() {
+        let mut proto_object = JSObject::new();
+        proto_object.proto = Heap::ERROR_PROTO;
+        proto_object.set_hidden("name", name)?;
+        proto_object.set_hidden("message", "")?;
+        let proto_ref = heap.alloc(proto_object);
+
+        let mut ctor_object = JSObject::from_func(constructor);
+        ctor_object.set_system("prototype", proto_ref)?;
+        let ctor_ref = heap.alloc(ctor_object);
+
+        heap.get_mut(proto_ref)
+            .set_hidden("constructor", ctor_ref)?;
+        heap.get_mut(Heap::GLOBAL).set_hidden(name, ctor_ref)?;
+    }
+    Ok(())
+}
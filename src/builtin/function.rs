@@ -1,8 +1,15 @@
-use crate::error::TypeError;
+use crate::ast::{expr::Expr, stmt::Stmt};
+use crate::error::{ParseError, TypeError};
 use crate::object::HostClass;
 /// The implementation of the builtin Function object.
 use crate::prelude::*;
-use crate::{function::CallContext, Exception, Heap, Interpreted, JSResult};
+use crate::{
+    function::CallContext, Exception, Heap, Interpretable, Interpreted, JSObject, JSRef, JSResult,
+};
+
+const BOUND_TARGET: &str = "[[BoundTargetFunction]]";
+const BOUND_THIS: &str = "[[BoundThis]]";
+const BOUND_ARGUMENTS: &str = "[[BoundArguments]]";
 
 pub static CLASS: HostClass = HostClass {
     name: "Function",
@@ -10,13 +17,41 @@ pub static CLASS: HostClass = HostClass {
     methods: &[
         ("call", function_proto_call),
         ("apply", function_proto_apply),
+        ("bind", function_proto_bind),
     ],
     static_methods: &[],
 };
 
-fn function_constructor(_call: CallContext, _heap: &mut Heap) -> JSResult<Interpreted> {
-    // TODO: eval arguments
-    todo!()
+/// `Function(arg1, ..., argN, body)` makes a function in the global scope.
+fn function_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let mut texts = Vec::new();
+    for i in 0..call.arguments.len() {
+        let text = call.arg_value(i, heap)?.stringify(heap)?;
+        texts.push(text.to_string());
+    }
+    let body = texts.pop().unwrap_or_default();
+    let params = texts.join(",");
+
+    let source = format!("(function ({}\n) {{\n{}\n}})", params, body);
+    let program = heap.parse(&source)?;
+
+    // `params` or `body` must not close the function early, e.g. `Function("}, function() {")`
+    let function = match program.body.body.as_slice() {
+        [stmt] => match &stmt.stmt {
+            Stmt::Expr(exprstmt) => match &exprstmt.expression.expr {
+                Expr::Function(_) => Some(&exprstmt.expression),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    };
+    let function = function.ok_or_else(|| {
+        let err = ParseError::want("a function body", JSON::from(body.as_str()));
+        Exception::Syntax(err)
+    })?;
+
+    heap.in_global_scope(|heap| function.interpret(heap))
 }
 
 fn function_proto_call(mut call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
@@ -41,9 +76,7 @@ fn function_proto_apply(call: CallContext, heap: &mut Heap) -> JSResult<Interpre
             let array = (heap.get(objref))
                 .as_array()
                 .ok_or_else(|| Exception::type_error(TypeError::NOT_ARRAYLIKE, object.clone()))?;
-            (array.storage.iter())
-                .map(|val| Interpreted::Value(val.clone()))
-                .collect()
+            array.values().map(Interpreted::Value).collect()
         }
         None => Vec::new(),
     };
@@ -55,3 +88,73 @@ fn function_proto_apply(call: CallContext, heap: &mut Heap) -> JSResult<Interpre
             .with_name(call.method_name),
     )
 }
+
+/// `f.bind(thisArg, ...args)`: a function that calls `f` with `thisArg` and `args` prepended.
+/// `new` on it constructs `f` and ignores `thisArg`, see [`unbind`].
+fn function_proto_bind(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let target_ref = call.this_ref;
+    if !heap.get(target_ref).is_callable() {
+        return Err(Exception::type_error(TypeError::NOT_CALLABLE, target_ref));
+    }
+    let bound_this = call.arg_value(0, heap)?.objectify(heap);
+    let bound_args = (call.arguments.iter().skip(1))
+        .map(|arg| arg.to_value(heap))
+        .collect::<JSResult<Vec<JSValue>>>()?;
+    let length = match heap.get(target_ref).get_own_value("length") {
+        Some(JSValue::Number(n)) => f64::max(n - bound_args.len() as f64, 0.0),
+        _ => 0.0,
+    };
+    let bound_args_ref = heap.alloc(JSObject::from_array(bound_args));
+
+    let mut function_object = JSObject::from_func(bound_function);
+    function_object.set_nonconf("length", length)?;
+    function_object.set_system(BOUND_TARGET, target_ref)?;
+    function_object.set_system(BOUND_THIS, bound_this)?;
+    function_object.set_system(BOUND_ARGUMENTS, bound_args_ref)?;
+    Ok(Interpreted::from(heap.alloc(function_object)))
+}
+
+/// The body of functions made by `.bind()`: [`Heap::execute`] calls their targets instead.
+/// A host function does not know its own object, so without `execute` there is no target.
+fn bound_function(call: CallContext, _heap: &mut Heap) -> JSResult<Interpreted> {
+    let callee = Interpreted::Member {
+        of: call.this_ref,
+        name: call.method_name,
+    };
+    Err(Exception::type_error(TypeError::NOT_CALLABLE, callee))
+}
+
+/// The function that `func_ref` calls, if it is made by `.bind()`.
+fn bound_target(func_ref: JSRef, heap: &Heap) -> Option<JSRef> {
+    let target = heap.get(func_ref).get_own_value(BOUND_TARGET)?;
+    target.to_ref().ok()
+}
+
+/// If `func_ref` is made by `.bind()`, turns `call` into a call of its target and returns it:
+/// the bound arguments go first, the bound `this` is used unless it is a `new` call.
+pub(crate) fn unbind(func_ref: JSRef, call: &mut CallContext, heap: &Heap) -> Option<JSRef> {
+    let target_ref = bound_target(func_ref, heap)?;
+    let function_object = heap.get(func_ref);
+    let bound_this = function_object.get_own_value(BOUND_THIS)?.to_ref().ok()?;
+    let bound_args_ref = function_object
+        .get_own_value(BOUND_ARGUMENTS)?
+        .to_ref()
+        .ok()?;
+    let bound_args = heap.get(bound_args_ref).as_array()?.values();
+    call.arguments
+        .splice(0..0, bound_args.map(Interpreted::Value));
+    match call.new_target {
+        None => call.this_ref = bound_this,
+        Some(new_target) if new_target == func_ref => call.new_target = Some(target_ref),
+        Some(_) => (),
+    }
+    Some(target_ref)
+}
+
+/// What `new` on `func_ref` constructs: the innermost target of bound functions.
+pub(crate) fn constructor_of(mut func_ref: JSRef, heap: &Heap) -> JSRef {
+    while let Some(target_ref) = bound_target(func_ref, heap) {
+        func_ref = target_ref;
+    }
+    func_ref
+}
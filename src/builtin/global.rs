@@ -1,42 +1,52 @@
+use crate::error::TypeError;
+use crate::object::ObjectValue;
 use crate::prelude::*;
-use crate::{CallContext, Heap, Interpreted, JSObject, JSResult, JSValue};
+use crate::{CallContext, Exception, Heap, Interpreted, JSObject, JSRef, JSResult, JSValue};
 
 /*
  *  parseInt
  */
-#[allow(clippy::manual_range_contains)]
+/// ES5+ `parseInt(string, radix)`: the longest prefix of digits in `radix` after an optional
+/// sign. Without a radix (or with `0`), only `0x`/`0X` switches to hexadecimal,
+/// a leading zero is still decimal: `parseInt('08')` is `8`.
 fn parse_int(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
-    let argument = call.arguments.first().unwrap_or(&Interpreted::VOID);
-    let value = argument.to_value(heap)?;
-    if let JSValue::Number(_) = value {
-        return Ok(Interpreted::Value(value));
+    let input = call.arg_value(0, heap)?.stringify(heap)?;
+    let radix = call.arg_value(1, heap)?.to_number(heap)?;
+
+    let mut s = input.as_str().trim_start();
+    let sign = if s.starts_with('-') { -1.0 } else { 1.0 };
+    if s.starts_with(['-', '+']) {
+        s = &s[1..];
     }
-    let value = value.stringify(heap)?;
-
-    let radix = call.arguments.get(1).unwrap_or(&Interpreted::VOID);
-    let radix = radix.to_value(heap)?.numberify(heap);
-    let mut radix = radix.unwrap_or(0.0) as u32;
-    if radix == 0 {
-        let (c0, c1) = {
-            let mut cs = value.chars();
-            let c0 = cs.next().unwrap_or('\0');
-            let c1 = cs.next().unwrap_or('\0');
-            (c0, c1)
-        };
-        match (c0, c1) {
-            ('0', 'x') | ('0', 'X') => radix = 16,
-            ('0', _) => radix = 8,
-            _ => radix = 10,
-        }
+
+    // ToInt32, `0` is "detect"
+    let radix = match radix.is_finite() {
+        true => radix.trunc().rem_euclid(4294967296.0) as u32 as i32,
+        false => 0,
+    };
+    let (mut radix, strip_prefix) = match radix {
+        0 => (10, true),
+        16 => (16, true),
+        2..=36 => (radix as u32, false),
+        _ => return Ok(Interpreted::NAN),
+    };
+    if strip_prefix && (s.starts_with("0x") || s.starts_with("0X")) {
+        s = &s[2..];
+        radix = 16;
     }
-    if radix < 2 || radix > 36 {
+
+    let end = s.find(|c: char| !c.is_digit(radix)).unwrap_or(s.len());
+    let digits = &s[..end];
+    if digits.is_empty() {
         return Ok(Interpreted::NAN);
     }
-
-    let result = i64::from_str_radix(&value, radix)
-        .map(|num| num as f64)
-        .unwrap_or(f64::NAN);
-    Ok(Interpreted::from(result))
+    let n = match radix {
+        10 => f64::from_str(digits).unwrap_or(f64::NAN),
+        _ => (digits.chars()).fold(0.0, |n, c| {
+            n * radix as f64 + c.to_digit(radix).unwrap() as f64
+        }),
+    };
+    Ok(Interpreted::from(sign * n))
 }
 
 #[allow(non_snake_case)]
@@ -49,6 +59,108 @@ fn global_parseFloat(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted
     Ok(Interpreted::from(result))
 }
 
+/*
+ *  isFinite, isNaN
+ */
+
+/// Unlike `Number.isFinite()`, the global one converts its argument with ToNumber first.
+#[allow(non_snake_case)]
+fn global_isFinite(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let n = call.arg_value(0, heap)?.to_number(heap)?;
+    Ok(Interpreted::from(n.is_finite()))
+}
+
+/// Unlike `Number.isNaN()`, the global one converts its argument with ToNumber first.
+#[allow(non_snake_case)]
+fn global_isNaN(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let n = call.arg_value(0, heap)?.to_number(heap)?;
+    Ok(Interpreted::from(n.is_nan()))
+}
+
+/*
+ *  queueMicrotask
+ */
+
+#[allow(non_snake_case)]
+fn global_queueMicrotask(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let callback = call.arg_value(0, heap)?;
+    let func_ref = match callback {
+        JSValue::Ref(func_ref) if heap.get(func_ref).is_callable() => func_ref,
+        _ => return Err(Exception::type_error(TypeError::NOT_CALLABLE, callback)),
+    };
+    heap.enqueue_microtask(func_ref);
+    Ok(Interpreted::VOID)
+}
+
+/*
+ *  structuredClone
+ */
+
+#[allow(non_snake_case)]
+fn global_structuredClone(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let value = call.arg_value(0, heap)?;
+    let mut clones = HashMap::new();
+    let clone = structured_clone(&value, &mut clones, heap)?;
+    Ok(Interpreted::from(clone))
+}
+
+/// Deep-copies `value`; `clones` maps every object seen so far to its copy,
+/// so shared sub-objects and cycles are copied once.
+fn structured_clone(
+    value: &JSValue,
+    clones: &mut HashMap<JSRef, JSRef>,
+    heap: &mut Heap,
+) -> JSResult<JSValue> {
+    let objref = match value {
+        JSValue::Ref(objref) if *objref != Heap::NULL => *objref,
+        _ => return Ok(value.clone()),
+    };
+    if let Some(&clone_ref) = clones.get(&objref) {
+        return Ok(JSValue::from(clone_ref));
+    }
+
+    let object = heap.get(objref);
+    let clone = match &object.value {
+        ObjectValue::HostFn(_) | ObjectValue::Closure(_) => {
+            return Err(Exception::type_error(TypeError::NOT_CLONEABLE, objref));
+        }
+        // the elements are cloned below
+        ObjectValue::Array(array) => {
+            JSObject::from_array_with_holes(vec![None; array.storage.len()])
+        }
+        ObjectValue::Boolean(_) | ObjectValue::Number(_) | ObjectValue::String(_) => {
+            let mut wrapper = JSObject::new();
+            wrapper.proto = object.proto;
+            wrapper.value = object.value.clone();
+            wrapper
+        }
+        ObjectValue::None => JSObject::new(),
+    };
+    let elements = object.as_array().map(|array| array.storage.clone());
+    let names = (object.ordered_properties())
+        .filter(|(_, prop)| prop.access.enumerable())
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+
+    // register the clone before going deeper
+    let clone_ref = heap.alloc(clone);
+    clones.insert(objref, clone_ref);
+
+    for (i, element) in elements.unwrap_or_default().into_iter().enumerate() {
+        if let Some(element) = element {
+            let element = structured_clone(&element, clones, heap)?;
+            heap.get_mut(clone_ref).as_array_mut().unwrap().storage[i] = Some(element);
+        }
+    }
+    for name in names {
+        // getters are called, the clone gets plain values
+        let value = Interpreted::member(objref, &name).to_value(heap)?;
+        let value = structured_clone(&value, clones, heap)?;
+        heap.get_mut(clone_ref).set_property(name.as_str(), value)?;
+    }
+    Ok(JSValue::from(clone_ref))
+}
+
 /*
  *  init
  */
@@ -56,14 +168,20 @@ fn global_parseFloat(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted
 pub fn init(heap: &mut Heap) -> JSResult<()> {
     let mut global = JSObject::new();
 
+    global.set_system("Infinity", f64::INFINITY)?;
     global.set_system("NaN", f64::NAN)?;
     global.set_system("undefined", JSValue::Undefined)?;
 
     global.set_hidden("global", Heap::GLOBAL)?;
+    global.set_hidden("globalThis", Heap::GLOBAL)?;
     global.set_system(Heap::SCOPE_THIS, Heap::GLOBAL)?;
 
     global.set_hidden("parseInt", heap.alloc_func(parse_int))?;
     global.set_hidden("parseFloat", heap.alloc_func(global_parseFloat))?;
+    global.set_hidden("isFinite", heap.alloc_func(global_isFinite))?;
+    global.set_hidden("isNaN", heap.alloc_func(global_isNaN))?;
+    global.set_hidden("queueMicrotask", heap.alloc_func(global_queueMicrotask))?;
+    global.set_hidden("structuredClone", heap.alloc_func(global_structuredClone))?;
 
     *heap.get_mut(Heap::GLOBAL) = global;
 
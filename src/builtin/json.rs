@@ -0,0 +1,174 @@
+//! The builtin `JSON` object.
+
+use crate::error::{ParseError, TypeError};
+use crate::object::ObjectValue;
+use crate::prelude::*;
+use crate::{CallContext, Exception, Heap, Interpreted, JSObject, JSRef, JSResult};
+
+static METHODS: &[(&str, crate::HostFn)] = &[("parse", json_parse), ("stringify", json_stringify)];
+
+fn json_parse(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    // TODO: reviver
+    let text = call.arg_value(0, heap)?.stringify(heap)?;
+    let json: JSON = serde_json::from_str(text.as_str()).map_err(|e| {
+        let err = e.to_string();
+        Exception::Syntax(ParseError::InvalidJSON { err })
+    })?;
+    Ok(Interpreted::from(heap.object_from_json(&json)))
+}
+
+fn json_stringify(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    // TODO: replacer
+    let value = call.arg_value(0, heap)?;
+    let indent: String = match call.arg_value(2, heap)? {
+        JSValue::Number(n) => " ".repeat(n.clamp(0.0, 10.0) as usize),
+        JSValue::String(s) => s.chars().take(10).collect(),
+        _ => String::new(),
+    };
+
+    let mut output = String::new();
+    let mut ancestors = vec![];
+    let written = write_json(&value, heap, &indent, "", &mut ancestors, &mut output)?;
+    Ok(match written {
+        true => Interpreted::from(output),
+        false => Interpreted::VOID,
+    })
+}
+
+/// Appends JSON for `value` to `output`.
+/// Returns `false` (and writes nothing) for values that JSON skips: `undefined`, functions.
+/// `ancestors` are the objects being serialized, a TypeError is thrown if `value` is one of them.
+fn write_json(
+    value: &JSValue,
+    heap: &mut Heap,
+    indent: &str,
+    current: &str,
+    ancestors: &mut Vec<JSRef>,
+    output: &mut String,
+) -> JSResult<bool> {
+    let objref = match value {
+        JSValue::Undefined => return Ok(false),
+        JSValue::Bool(b) => {
+            output.push_str(if *b { "true" } else { "false" });
+            return Ok(true);
+        }
+        JSValue::Number(n) if !n.is_finite() => {
+            output.push_str("null");
+            return Ok(true);
+        }
+        JSValue::Number(_) => {
+            output.push_str(value.stringify(heap)?.as_str());
+            return Ok(true);
+        }
+        JSValue::BigInt(_) => {
+            return Err(Exception::type_error(
+                TypeError::BIGINT_TO_JSON,
+                value.clone(),
+            ));
+        }
+        JSValue::String(s) => {
+            output.push_str(&JSON::from(s.as_str()).to_string());
+            return Ok(true);
+        }
+        JSValue::Ref(Heap::NULL) => {
+            output.push_str("null");
+            return Ok(true);
+        }
+        JSValue::Ref(r) => *r,
+    };
+
+    let object = heap.get(objref);
+    if object.is_callable() {
+        return Ok(false);
+    }
+    if let Some(primitive) = object.to_primitive() {
+        return write_json(&primitive, heap, indent, current, ancestors, output);
+    }
+    if ancestors.contains(&objref) {
+        return Err(Exception::type_error(TypeError::CIRCULAR_JSON, objref));
+    }
+    ancestors.push(objref);
+    let result = write_json_object(objref, heap, indent, current, ancestors, output);
+    ancestors.pop();
+    result.map(|()| true)
+}
+
+/// Appends JSON for an Array or an Object.
+fn write_json_object(
+    objref: JSRef,
+    heap: &mut Heap,
+    indent: &str,
+    current: &str,
+    ancestors: &mut Vec<JSRef>,
+    output: &mut String,
+) -> JSResult<()> {
+    let object = heap.get(objref);
+
+    let inner = format!("{}{}", current, indent);
+    let (separator, closing) = match indent.is_empty() {
+        true => (String::new(), String::new()),
+        false => (format!("\n{}", inner), format!("\n{}", current)),
+    };
+
+    if let ObjectValue::Array(array) = &object.value {
+        let values = array.values().collect::<Vec<_>>();
+        output.push('[');
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                output.push(',');
+            }
+            output.push_str(&separator);
+            if !write_json(value, heap, indent, &inner, ancestors, output)? {
+                output.push_str("null");
+            }
+        }
+        if !values.is_empty() {
+            output.push_str(&closing);
+        }
+        output.push(']');
+        return Ok(());
+    }
+
+    let keys = (object.ordered_properties())
+        .filter(|(_, prop)| prop.access.enumerable())
+        .map(|(key, _)| key.clone())
+        .collect::<Vec<_>>();
+
+    output.push('{');
+    let mut empty = true;
+    for key in keys.iter() {
+        let value = &Interpreted::member(objref, key).to_value(heap)?;
+        let mut item = String::new();
+        item.push_str(&separator);
+        item.push_str(&JSON::from(key.as_str()).to_string());
+        item.push(':');
+        if !indent.is_empty() {
+            item.push(' ');
+        }
+        if !write_json(value, heap, indent, &inner, ancestors, &mut item)? {
+            continue;
+        }
+        if !empty {
+            output.push(',');
+        }
+        output.push_str(&item);
+        empty = false;
+    }
+    if !empty {
+        output.push_str(&closing);
+    }
+    output.push('}');
+    Ok(())
+}
+
+pub fn init(heap: &mut Heap) -> JSResult<()> {
+    let mut json_object = JSObject::new();
+    for &(name, func) in METHODS.iter() {
+        let func = heap.alloc_func(func);
+        json_object.set_hidden(name, func)?;
+    }
+
+    let json_ref = heap.alloc(json_object);
+    heap.get_mut(Heap::GLOBAL).set_hidden("JSON", json_ref)?;
+    Ok(())
+}
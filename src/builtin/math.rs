@@ -0,0 +1,220 @@
+//! The builtin `Math` object.
+
+use crate::prelude::*;
+use crate::{CallContext, Heap, Interpreted, JSObject, JSResult};
+
+static METHODS: &[(&str, crate::HostFn)] = &[
+    ("abs", math_abs),
+    ("acos", math_acos),
+    ("asin", math_asin),
+    ("atan", math_atan),
+    ("atan2", math_atan2),
+    ("cbrt", math_cbrt),
+    ("ceil", math_ceil),
+    ("cos", math_cos),
+    ("cosh", math_cosh),
+    ("exp", math_exp),
+    ("floor", math_floor),
+    ("hypot", math_hypot),
+    ("log", math_log),
+    ("log10", math_log10),
+    ("log2", math_log2),
+    ("max", math_max),
+    ("min", math_min),
+    ("pow", math_pow),
+    ("round", math_round),
+    ("sign", math_sign),
+    ("sin", math_sin),
+    ("sinh", math_sinh),
+    ("sqrt", math_sqrt),
+    ("tan", math_tan),
+    ("tanh", math_tanh),
+    ("trunc", math_trunc),
+];
+
+static CONSTANTS: &[(&str, JSNumber)] = &[
+    ("E", core::f64::consts::E),
+    ("LN10", core::f64::consts::LN_10),
+    ("LN2", core::f64::consts::LN_2),
+    ("LOG10E", core::f64::consts::LOG10_E),
+    ("LOG2E", core::f64::consts::LOG2_E),
+    ("PI", core::f64::consts::PI),
+    ("SQRT1_2", core::f64::consts::FRAC_1_SQRT_2),
+    ("SQRT2", core::f64::consts::SQRT_2),
+];
+
+/// `Math` methods coerce all their arguments to numbers.
+fn arg_number(call: &CallContext, index: usize, heap: &mut Heap) -> JSResult<JSNumber> {
+    call.arg_value(index, heap)?.to_number(heap)
+}
+
+fn math_abs(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.abs()))
+}
+
+fn math_acos(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.acos()))
+}
+
+fn math_asin(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.asin()))
+}
+
+fn math_atan(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.atan()))
+}
+
+fn math_atan2(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let y = arg_number(&call, 0, heap)?;
+    let x = arg_number(&call, 1, heap)?;
+    Ok(Interpreted::from(y.atan2(x)))
+}
+
+fn math_cbrt(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.cbrt()))
+}
+
+fn math_ceil(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.ceil()))
+}
+
+fn math_cos(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.cos()))
+}
+
+fn math_cosh(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.cosh()))
+}
+
+fn math_exp(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.exp()))
+}
+
+fn math_floor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.floor()))
+}
+
+fn math_hypot(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let mut result: JSNumber = 0.0;
+    for i in 0..call.arguments.len() {
+        // f64::hypot is Infinity for (Infinity, NaN), as Math.hypot is.
+        result = result.hypot(arg_number(&call, i, heap)?);
+    }
+    Ok(Interpreted::from(result))
+}
+
+fn math_log(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.ln()))
+}
+
+fn math_log10(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.log10()))
+}
+
+fn math_log2(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.log2()))
+}
+
+fn math_max(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let mut result = f64::NEG_INFINITY;
+    for i in 0..call.arguments.len() {
+        let n = arg_number(&call, i, heap)?;
+        // f64::max ignores NaN, Math.max does not.
+        if n.is_nan() || result.is_nan() {
+            result = f64::NAN;
+        } else if n > result {
+            result = n;
+        }
+    }
+    Ok(Interpreted::from(result))
+}
+
+fn math_min(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let mut result = f64::INFINITY;
+    for i in 0..call.arguments.len() {
+        let n = arg_number(&call, i, heap)?;
+        if n.is_nan() || result.is_nan() {
+            result = f64::NAN;
+        } else if n < result {
+            result = n;
+        }
+    }
+    Ok(Interpreted::from(result))
+}
+
+fn math_pow(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let base = arg_number(&call, 0, heap)?;
+    let exponent = arg_number(&call, 1, heap)?;
+    Ok(Interpreted::from(pow(base, exponent)))
+}
+
+/// `base ** exponent`, also `Math.pow(base, exponent)`.
+pub fn pow(base: f64, exponent: f64) -> f64 {
+    // unlike f64::powf, `1 ** NaN` is NaN in JavaScript
+    match (base, exponent) {
+        (_, e) if e.is_nan() => f64::NAN,
+        (b, e) if b.abs() == 1.0 && e.is_infinite() => f64::NAN,
+        (b, e) => b.powf(e),
+    }
+}
+
+fn math_round(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    // f64::round() rounds half away from zero, Math.round() rounds half up.
+    let n = arg_number(&call, 0, heap)?;
+    let floor = n.floor();
+    let result = if n - floor >= 0.5 { floor + 1.0 } else { floor };
+    // -0.5 <= n < 0 rounds to -0
+    match result == 0.0 && n < 0.0 {
+        true => Ok(Interpreted::from(-0.0)),
+        false => Ok(Interpreted::from(result)),
+    }
+}
+
+fn math_sign(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let n = arg_number(&call, 0, heap)?;
+    let result = match n {
+        _ if n > 0.0 => 1.0,
+        _ if n < 0.0 => -1.0,
+        _ => n, // NaN, 0, -0
+    };
+    Ok(Interpreted::from(result))
+}
+
+fn math_sin(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.sin()))
+}
+
+fn math_sinh(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.sinh()))
+}
+
+fn math_sqrt(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.sqrt()))
+}
+
+fn math_tan(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.tan()))
+}
+
+fn math_tanh(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.tanh()))
+}
+
+fn math_trunc(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    Ok(Interpreted::from(arg_number(&call, 0, heap)?.trunc()))
+}
+
+pub fn init(heap: &mut Heap) -> JSResult<()> {
+    let mut math_object = JSObject::new();
+    for &(name, func) in METHODS.iter() {
+        let func = heap.alloc_func(func);
+        math_object.set_hidden(name, func)?;
+    }
+    for &(name, value) in CONSTANTS.iter() {
+        math_object.set_system(name, value)?;
+    }
+
+    let math_ref = heap.alloc(math_object);
+    heap.get_mut(Heap::GLOBAL).set_hidden("Math", math_ref)?;
+    Ok(())
+}
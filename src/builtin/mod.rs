@@ -1,14 +1,20 @@
+pub mod abort;
 pub mod array;
 pub mod boolean;
+pub mod console;
 pub mod error;
 pub mod function;
 pub mod global;
+pub mod json;
+pub mod math;
+pub mod number;
 pub mod object;
+pub mod reflect;
 pub mod string;
 
-use crate::{Heap, JSResult};
+use crate::{Heap, HeapBuilder, JSResult};
 
-pub fn init(heap: &mut Heap) -> JSResult<()> {
+pub fn init(heap: &mut Heap, globals: &HeapBuilder) -> JSResult<()> {
     global::init(heap)?;
     heap.init_class(Heap::OBJECT_PROTO, &object::CLASS)?;
     {
@@ -24,13 +30,33 @@ pub fn init(heap: &mut Heap) -> JSResult<()> {
 
     heap.init_class(Heap::FUNCTION_PROTO, &function::CLASS)?;
     heap.init_class(Heap::ARRAY_PROTO, &array::CLASS)?;
+    array::init_iterators(heap)?;
     heap.init_class(Heap::BOOLEAN_PROTO, &boolean::CLASS)?;
+    heap.init_class(Heap::NUMBER_PROTO, &number::CLASS)?;
+    number::init_statics(heap)?;
     heap.init_class(Heap::STRING_PROTO, &string::CLASS)?;
+    string::init_iterator(heap)?;
     heap.init_class(Heap::ERROR_PROTO, &error::CLASS)?;
     {
         let error_proto = heap.get_mut(Heap::ERROR_PROTO);
         error_proto.set_hidden("name", "Error")?;
         error_proto.set_hidden("message", "")?;
     }
+
+    reflect::init(heap)?;
+    abort::init(heap)?;
+
+    if globals.error_types {
+        error::init_subclasses(heap)?;
+    }
+    if globals.math {
+        math::init(heap)?;
+    }
+    if globals.json {
+        json::init(heap)?;
+    }
+    if globals.console {
+        console::init(heap)?;
+    }
     Ok(())
 }
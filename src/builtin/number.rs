@@ -0,0 +1,370 @@
+use num_traits::ToPrimitive;
+
+use crate::error::RangeError;
+use crate::prelude::*;
+use crate::{
+    object::HostClass, CallContext, Exception, Heap, Interpreted, JSObject, JSRef, JSResult,
+};
+
+pub static CLASS: HostClass = HostClass {
+    name: "Number",
+    constructor: number_constructor,
+    methods: &[
+        ("toExponential", number_proto_toExponential),
+        ("toLocaleString", number_proto_toLocaleString),
+        ("toPrecision", number_proto_toPrecision),
+        ("toString", number_proto_toString),
+        ("valueOf", number_proto_valueOf),
+    ],
+    static_methods: &[
+        ("isFinite", number_object_isFinite),
+        ("isNaN", number_object_isNaN),
+    ],
+};
+
+static CONSTANTS: &[(&str, JSNumber)] = &[
+    ("EPSILON", f64::EPSILON),
+    ("MAX_SAFE_INTEGER", 9007199254740991.0),
+    ("MAX_VALUE", f64::MAX),
+    ("MIN_SAFE_INTEGER", -9007199254740991.0),
+    ("MIN_VALUE", 5e-324),
+    ("NaN", f64::NAN),
+    ("NEGATIVE_INFINITY", f64::NEG_INFINITY),
+    ("POSITIVE_INFINITY", f64::INFINITY),
+];
+
+/// `Number.parseInt`/`Number.parseFloat` are the global `parseInt`/`parseFloat` themselves.
+pub fn init_statics(heap: &mut Heap) -> JSResult<()> {
+    let number_ref = heap.lookup_path(&["Number"])?.to_ref(heap)?;
+    for &(name, value) in CONSTANTS.iter() {
+        heap.get_mut(number_ref).set_system(name, value)?;
+    }
+    for name in ["parseInt", "parseFloat"] {
+        let func = (heap.get(Heap::GLOBAL).get_own_value(name))
+            .ok_or_else(|| Exception::no_reference(name))?;
+        heap.get_mut(number_ref).set_hidden(name, func)?;
+    }
+    Ok(())
+}
+
+/// `Number.isFinite()` does not convert its argument: only numbers can be finite.
+#[allow(non_snake_case)]
+fn number_object_isFinite(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let finite = matches!(call.arg_value(0, heap)?, JSValue::Number(n) if n.is_finite());
+    Ok(Interpreted::from(finite))
+}
+
+/// `Number.isNaN()` does not convert its argument: only `NaN` itself is `NaN`.
+#[allow(non_snake_case)]
+fn number_object_isNaN(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let nan = matches!(call.arg_value(0, heap)?, JSValue::Number(n) if n.is_nan());
+    Ok(Interpreted::from(nan))
+}
+
+fn number_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let n = match call.arguments.first() {
+        None => 0.0,
+        Some(arg) => match arg.to_value(heap)? {
+            // the only explicit conversion of bigints to numbers
+            JSValue::BigInt(big) => big.to_f64().unwrap_or(f64::NAN),
+            value => value.to_number(heap)?,
+        },
+    };
+
+    if !heap.smells_fresh(call.this_ref) {
+        return Ok(Interpreted::from(n));
+    }
+
+    *heap.get_mut(call.this_ref) = JSObject::from_number(n);
+    Ok(Interpreted::VOID)
+}
+
+fn object_to_number(this_ref: JSRef, heap: &Heap) -> JSResult<JSNumber> {
+    match heap.get(this_ref).to_primitive() {
+        Some(JSValue::Number(n)) => Ok(n),
+        _ => Err(Exception::instance_required(this_ref, "Number")),
+    }
+}
+
+#[allow(non_snake_case)]
+fn number_proto_toString(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let n = object_to_number(call.this_ref, heap)?;
+    let radix = match call.arg_value(0, heap)? {
+        JSValue::Undefined => 10.0,
+        radix => radix.to_number(heap)?.trunc(),
+    };
+    if !(2.0..=36.0).contains(&radix) {
+        return Err(Exception::range_error(RangeError::INVALID_RADIX, radix));
+    }
+
+    let s = match radix as u32 {
+        10 => JSValue::from(n).stringify(heap)?.to_string(),
+        radix => to_radix_string(n, radix),
+    };
+    Ok(Interpreted::from(s))
+}
+
+/// There is no `Intl`: locales and options are ignored, this is `.toString()`.
+#[allow(non_snake_case)]
+fn number_proto_toLocaleString(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let n = object_to_number(call.this_ref, heap)?;
+    Ok(Interpreted::from(JSValue::from(n).stringify(heap)?))
+}
+
+/// ToIntegerOrInfinity of the argument `i`, `None` if it is `undefined`.
+fn integer_arg(call: &CallContext, i: usize, heap: &mut Heap) -> JSResult<Option<f64>> {
+    match call.arg_value(i, heap)? {
+        JSValue::Undefined => Ok(None),
+        value => {
+            let n = value.to_number(heap)?;
+            Ok(Some(if n.is_nan() { 0.0 } else { n.trunc() }))
+        }
+    }
+}
+
+/// The first `count` decimal digits of a finite `n > 0` and the decimal exponent of the first
+/// one. The exact decimal value is rounded half up, as `toPrecision`/`toExponential` require.
+fn decimal_digits(n: JSNumber, count: usize) -> (Vec<u8>, i32) {
+    // 800 digits are more than enough for the exact value of any double
+    let formatted = format!("{:.800e}", n);
+    let (mantissa, exponent) = formatted.split_once('e').unwrap();
+    let mut exponent = exponent.parse::<i32>().unwrap();
+    let exact = (mantissa.bytes())
+        .filter(u8::is_ascii_digit)
+        .map(|b| b - b'0')
+        .collect::<Vec<u8>>();
+
+    let mut digits = exact[..count].to_vec();
+    if exact[count] >= 5 {
+        // round up, propagating the carry
+        match digits.iter().rposition(|&d| d < 9) {
+            Some(i) => {
+                digits[i] += 1;
+                digits[i + 1..].fill(0);
+            }
+            None => {
+                digits.fill(0);
+                digits[0] = 1;
+                exponent += 1;
+            }
+        }
+    }
+    (digits, exponent)
+}
+
+/// The shortest decimal digits that read back as a finite `n > 0`, with the exponent.
+fn shortest_digits(n: JSNumber) -> (Vec<u8>, i32) {
+    let formatted = format!("{:e}", n);
+    let (mantissa, exponent) = formatted.split_once('e').unwrap();
+    let digits = (mantissa.bytes())
+        .filter(u8::is_ascii_digit)
+        .map(|b| b - b'0')
+        .collect();
+    (digits, exponent.parse().unwrap())
+}
+
+/// ES2015+ Number::toString(x): the shortest digits that read back as `n`, in the exponential
+/// notation if the decimal exponent is below -6 or above 20.
+pub(crate) fn to_decimal_string(n: JSNumber) -> String {
+    if n.is_nan() {
+        return "NaN".to_string();
+    }
+    if n == 0.0 {
+        return "0".to_string();
+    }
+    if n.is_infinite() {
+        return (if n > 0.0 { "Infinity" } else { "-Infinity" }).to_string();
+    }
+    if n < 0.0 {
+        return format!("-{}", to_decimal_string(-n));
+    }
+
+    let (digits, exponent) = shortest_digits(n);
+    let digit = |d: &u8| char::from(b'0' + d);
+    let k = digits.len() as i32;
+    let point = exponent + 1; // the position of the decimal point
+    if k <= point && point <= 21 {
+        let mut s = digits.iter().map(digit).collect::<String>();
+        s.push_str(&"0".repeat((point - k) as usize));
+        s
+    } else if 0 < point && point <= 21 {
+        let (integer, fraction) = digits.split_at(point as usize);
+        let integer = integer.iter().map(digit).collect::<String>();
+        let fraction = fraction.iter().map(digit).collect::<String>();
+        format!("{}.{}", integer, fraction)
+    } else if -6 < point && point <= 0 {
+        let zeros = "0".repeat(-point as usize);
+        format!(
+            "0.{}{}",
+            zeros,
+            digits.iter().map(digit).collect::<String>()
+        )
+    } else {
+        exponential_notation(&digits, exponent)
+    }
+}
+
+/// `d.ddde+x`
+fn exponential_notation(digits: &[u8], exponent: i32) -> String {
+    let digit = |d: &u8| char::from(b'0' + d);
+    let mut s = String::new();
+    s.push(digit(&digits[0]));
+    if digits.len() > 1 {
+        s.push('.');
+        s.extend(digits[1..].iter().map(digit));
+    }
+    let sign = if exponent < 0 { '-' } else { '+' };
+    s + &format!("e{}{}", sign, exponent.abs())
+}
+
+#[allow(non_snake_case)]
+fn number_proto_toExponential(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let n = object_to_number(call.this_ref, heap)?;
+    let fraction_digits = integer_arg(&call, 0, heap)?;
+    if !n.is_finite() {
+        let s = JSValue::from(n).stringify(heap)?;
+        return Ok(Interpreted::from(s));
+    }
+    if let Some(f) = fraction_digits {
+        if !(0.0..=100.0).contains(&f) {
+            return Err(Exception::range_error(RangeError::INVALID_DIGITS, f));
+        }
+    }
+
+    let sign = if n < 0.0 { "-" } else { "" };
+    let x = n.abs();
+    let (digits, exponent) = match fraction_digits {
+        _ if x == 0.0 => (vec![0; fraction_digits.unwrap_or(0.0) as usize + 1], 0),
+        None => shortest_digits(x),
+        Some(f) => decimal_digits(x, f as usize + 1),
+    };
+    let s = sign.to_string() + &exponential_notation(&digits, exponent);
+    Ok(Interpreted::from(s))
+}
+
+#[allow(non_snake_case)]
+fn number_proto_toPrecision(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let n = object_to_number(call.this_ref, heap)?;
+    let precision = match integer_arg(&call, 0, heap)? {
+        Some(p) if n.is_finite() => p,
+        _ => {
+            let s = JSValue::from(n).stringify(heap)?;
+            return Ok(Interpreted::from(s));
+        }
+    };
+    if !(1.0..=100.0).contains(&precision) {
+        return Err(Exception::range_error(
+            RangeError::INVALID_PRECISION,
+            precision,
+        ));
+    }
+
+    let p = precision as usize;
+    let sign = if n < 0.0 { "-" } else { "" };
+    let x = n.abs();
+    let (digits, e) = match x == 0.0 {
+        true => (vec![0; p], 0),
+        false => decimal_digits(x, p),
+    };
+
+    let digit = |d: &u8| char::from(b'0' + d);
+    let s = if e < -6 || e >= p as i32 {
+        exponential_notation(&digits, e)
+    } else if e >= 0 {
+        let (integer, fraction) = digits.split_at(e as usize + 1);
+        let mut s = integer.iter().map(digit).collect::<String>();
+        if !fraction.is_empty() {
+            s.push('.');
+            s.extend(fraction.iter().map(digit));
+        }
+        s
+    } else {
+        let zeros = "0".repeat((-e - 1) as usize);
+        format!(
+            "0.{}{}",
+            zeros,
+            digits.iter().map(digit).collect::<String>()
+        )
+    };
+    Ok(Interpreted::from(sign.to_string() + &s))
+}
+
+#[allow(non_snake_case)]
+fn number_proto_valueOf(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let n = object_to_number(call.this_ref, heap)?;
+    Ok(Interpreted::from(n))
+}
+
+/// Formats `n` in base `radix` (2..=36), producing the shortest fraction that reads back
+/// as `n`. This follows V8's `DoubleToRadixCString`.
+fn to_radix_string(n: JSNumber, radix: u32) -> String {
+    if n.is_nan() {
+        return "NaN".to_string();
+    }
+    if n.is_infinite() {
+        return (if n > 0.0 { "Infinity" } else { "-Infinity" }).to_string();
+    }
+    if n < 0.0 {
+        return format!("-{}", to_radix_string(-n, radix));
+    }
+
+    let digit = |d: u32| char::from_digit(d, radix).unwrap();
+    let fradix = radix as f64;
+
+    let mut integer = n.trunc();
+    let mut fraction = n - integer;
+
+    // the distance to the next representable double, halved: digits below it are noise.
+    let next = f64::from_bits(n.to_bits() + 1);
+    let mut delta = f64::max(0.5 * (next - n), f64::from_bits(1));
+
+    let mut fraction_digits: Vec<u32> = vec![];
+    if fraction >= delta {
+        loop {
+            fraction *= fradix;
+            delta *= fradix;
+            let d = fraction.trunc();
+            fraction_digits.push(d as u32);
+            fraction -= d;
+            if (fraction > 0.5 || (fraction == 0.5 && (d as u32) & 1 == 1))
+                && fraction + delta > 1.0
+            {
+                // round up, propagating the carry
+                loop {
+                    match fraction_digits.pop() {
+                        None => {
+                            integer += 1.0;
+                            break;
+                        }
+                        Some(d) if d + 1 < radix => {
+                            fraction_digits.push(d + 1);
+                            break;
+                        }
+                        Some(_) => (),
+                    }
+                }
+                break;
+            }
+            if fraction < delta {
+                break;
+            }
+        }
+    }
+
+    let mut integer_digits = vec![];
+    loop {
+        let d = integer % fradix;
+        integer_digits.push(digit(d as u32));
+        integer = (integer - d) / fradix;
+        if integer < 1.0 {
+            break;
+        }
+    }
+
+    let mut s: String = integer_digits.into_iter().rev().collect();
+    if !fraction_digits.is_empty() {
+        s.push('.');
+        s.extend(fraction_digits.into_iter().map(digit));
+    }
+    s
+}
@@ -1,8 +1,9 @@
 use crate::error::TypeError;
+use crate::interpret::Iteration;
 use crate::prelude::*;
 use crate::{
-    object::Access, object::HostClass, CallContext, Exception, Heap, Interpreted, JSObject, JSRef,
-    JSResult,
+    object::Access, object::Content, object::HostClass, object::ObjectValue, CallContext,
+    Exception, Heap, Interpreted, JSObject, JSRef, JSResult,
 };
 
 pub static CLASS: HostClass = HostClass {
@@ -17,12 +18,20 @@ pub static CLASS: HostClass = HostClass {
         ("create", object_object_create),
         ("defineProperties", object_object_defineProperties),
         ("defineProperty", object_object_defineProperty),
+        ("entries", object_object_entries),
         (
             "getOwnPropertyDescriptor",
             object_object_getOwnPropertyDescriptor,
         ),
+        ("groupBy", object_object_groupBy),
         ("is", object_object_is),
+        ("isExtensible", object_object_isExtensible),
+        ("isSealed", object_object_isSealed),
+        ("keys", object_object_keys),
+        ("preventExtensions", object_object_preventExtensions),
+        ("seal", object_object_seal),
         ("setPrototypeOf", object_object_setPrototypeOf),
+        ("values", object_object_values),
     ],
 };
 
@@ -38,14 +47,40 @@ fn object_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interprete
 #[allow(non_snake_case)]
 fn object_proto_hasOwnProperty(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
     let propname = call.arg_value(0, heap)?.stringify(heap)?;
-    // TODO: avoid calling getters, if any
     let found = heap.get(call.this_ref).get_own_value(&propname).is_some();
     Ok(Interpreted::from(found))
 }
 
+/// `[object <Tag>]`, where the tag is the kind of the object.
+/// `this` can't be `undefined` here, so `.call(undefined)` gives `Null` too.
 #[allow(non_snake_case)]
-fn object_proto_toString(_call: CallContext, _heap: &mut Heap) -> JSResult<Interpreted> {
-    Ok(Interpreted::from("[object Object]"))
+pub(crate) fn object_proto_toString(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let tag = if call.this_ref == Heap::NULL {
+        "Null"
+    } else {
+        let object = heap.get(call.this_ref);
+        match &object.value {
+            ObjectValue::Array(_) => "Array",
+            ObjectValue::HostFn(_) | ObjectValue::Closure(_) => "Function",
+            ObjectValue::Boolean(_) => "Boolean",
+            ObjectValue::Number(_) => "Number",
+            ObjectValue::String(_) => "String",
+            ObjectValue::None if is_error(object.proto, heap) => "Error",
+            ObjectValue::None => "Object",
+        }
+    };
+    Ok(Interpreted::from(format!("[object {}]", tag)))
+}
+
+/// Is `Error.prototype` on the prototype chain starting at `proto`?
+fn is_error(mut proto: JSRef, heap: &Heap) -> bool {
+    while proto != Heap::NULL {
+        if proto == Heap::ERROR_PROTO {
+            return true;
+        }
+        proto = heap.get(proto).proto;
+    }
+    false
 }
 
 #[cfg(feature = "std")]
@@ -81,6 +116,80 @@ fn object_object_create(call: CallContext, heap: &mut Heap) -> JSResult<Interpre
     Ok(Interpreted::from(objref))
 }
 
+/// The object of `Object.keys(value)` and alike, with its enumerable own keys in order.
+fn enumerable_keys(call: &CallContext, heap: &mut Heap) -> JSResult<(JSRef, Vec<JSString>)> {
+    let value = call.arg_value(0, heap)?;
+    let objref = match value.objectify(heap) {
+        Heap::NULL => return Err(Exception::type_error(TypeError::NOT_AN_OBJECT, value)),
+        objref => objref,
+    };
+    Ok((objref, heap.get(objref).enumerable_keys()))
+}
+
+fn object_object_keys(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let (_, keys) = enumerable_keys(&call, heap)?;
+    let keys = keys.into_iter().map(JSValue::from).collect();
+    let keys_ref = heap.alloc(JSObject::from_array(keys));
+    Ok(Interpreted::from(keys_ref))
+}
+
+fn object_object_values(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let (objref, keys) = enumerable_keys(&call, heap)?;
+    let mut values = vec![];
+    for key in keys.iter() {
+        values.push(Interpreted::member(objref, key).to_value(heap)?);
+    }
+    let values_ref = heap.alloc(JSObject::from_array(values));
+    Ok(Interpreted::from(values_ref))
+}
+
+fn object_object_entries(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let (objref, keys) = enumerable_keys(&call, heap)?;
+    let mut entries = vec![];
+    for key in keys.into_iter() {
+        let value = Interpreted::member(objref, &key).to_value(heap)?;
+        let entry = JSObject::from_array(vec![JSValue::from(key), value]);
+        entries.push(JSValue::from(heap.alloc(entry)));
+    }
+    let entries_ref = heap.alloc(JSObject::from_array(entries));
+    Ok(Interpreted::from(entries_ref))
+}
+
+/// `Object.groupBy(items, callback)`: a null-prototype object with arrays of `items`
+/// under the keys given by `callback(item, index)`, both in order.
+#[allow(non_snake_case)]
+fn object_object_groupBy(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let items = call.arg_value(0, heap)?;
+    let callback = call.arg_value(1, heap)?;
+    let func_ref = match callback {
+        JSValue::Ref(r) if heap.get(r).is_callable() => r,
+        _ => return Err(Exception::type_error(TypeError::NOT_CALLABLE, callback)),
+    };
+
+    let mut groups: IndexMap<JSString, Vec<JSValue>> = IndexMap::default();
+    let mut iteration = Iteration::new(items, heap)?;
+    let mut index = 0;
+    while let Some(item) = iteration.next(heap)? {
+        let arguments = vec![
+            Interpreted::from(item.clone()),
+            Interpreted::from(index as f64),
+        ];
+        let call = CallContext::from(arguments).with_this(Heap::GLOBAL);
+        let key = heap.execute(func_ref, call)?.to_value(heap)?;
+        let key = key.stringify(heap)?;
+        groups.entry(key).or_default().push(item);
+        index += 1;
+    }
+
+    let mut object = JSObject::new();
+    object.proto = Heap::NULL;
+    for (key, items) in groups {
+        let items_ref = heap.alloc(JSObject::from_array(items));
+        object.set_property(&key, items_ref)?;
+    }
+    Ok(Interpreted::from(heap.alloc(object)))
+}
+
 fn object_object_is(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
     use JSValue::*;
 
@@ -123,13 +232,20 @@ fn object_object_getOwnPropertyDescriptor(
         None => return Ok(Interpreted::VOID),
     };
 
-    let value = prop.content.to_value()?;
-
     let mut descriptor_object = JSObject::new();
-    descriptor_object.set_property("value", value)?;
+    match prop.content {
+        Content::Value(value) => {
+            descriptor_object.set_property("value", value)?;
+            descriptor_object.set_property("writable", prop.access.writable())?;
+        }
+        Content::Accessor { get, set } => {
+            let to_value = |func: Option<JSRef>| func.map_or(JSValue::Undefined, JSValue::from);
+            descriptor_object.set_property("get", to_value(get))?;
+            descriptor_object.set_property("set", to_value(set))?;
+        }
+    }
     descriptor_object.set_property("configurable", prop.access.configurable())?;
     descriptor_object.set_property("enumerable", prop.access.enumerable())?;
-    descriptor_object.set_property("writable", prop.access.writable())?;
 
     let descriptor_ref = heap.alloc(descriptor_object);
     Ok(Interpreted::from(descriptor_ref))
@@ -160,7 +276,21 @@ fn define_property(
             return Err(Exception::type_error(TypeError::INVALID_DESCRIPTOR, what));
         }
 
-        todo!()
+        let accessor = |name: &str| match get_value(descriptor, name) {
+            JSValue::Undefined => Ok(None),
+            JSValue::Ref(func) if heap.get(func).is_callable() => Ok(Some(func)),
+            value => Err(Exception::attr_type_error(
+                TypeError::NOT_CALLABLE,
+                value,
+                name,
+            )),
+        };
+        let getter = accessor("get")?;
+        let setter = accessor("set")?;
+        let access = Access::new(configurable, enumerable, false);
+
+        let object = heap.get_mut(objref);
+        object.set_accessors(propname.as_str(), getter, setter, access)?;
     } else {
         let value = get_value(descriptor, "value");
         let writable = get_bool(descriptor, "writable");
@@ -184,7 +314,7 @@ fn object_object_defineProperty(call: CallContext, heap: &mut Heap) -> JSResult<
 }
 
 fn define_properties(objref: JSRef, descs_ref: JSRef, heap: &mut Heap) -> JSResult<()> {
-    let mut pairs: Vec<(JSString, JSRef)> = (heap.get(descs_ref).properties.iter())
+    let pairs: Vec<(JSString, JSRef)> = (heap.get(descs_ref).ordered_properties())
         .map(|(prop, desc)| {
             let descref = match desc.to_ref() {
                 Some(descref) => descref,
@@ -197,7 +327,7 @@ fn define_properties(objref: JSRef, descs_ref: JSRef, heap: &mut Heap) -> JSResu
         })
         .collect::<JSResult<_>>()?;
 
-    while let Some((prop, descref)) = pairs.pop() {
+    for (prop, descref) in pairs {
         define_property(objref, prop, descref, heap)?;
     }
     Ok(())
@@ -219,8 +349,51 @@ fn object_object_setPrototypeOf(call: CallContext, heap: &mut Heap) -> JSResult<
     let proto_arg = call.arg_value(1, heap)?;
     if let Ok(protoref) = proto_arg.to_ref() {
         let object = heap.get_mut(objref);
+        if !object.is_extensible() && object.proto != protoref {
+            let what = Interpreted::from(objref);
+            return Err(Exception::type_error(TypeError::NOT_EXTENSIBLE, what));
+        }
         object.proto = protoref;
     }
 
     Ok(Interpreted::from(objref))
 }
+
+#[allow(non_snake_case)]
+fn object_object_isExtensible(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let extensible = match call.arg_value(0, heap)? {
+        JSValue::Ref(objref) if objref != Heap::NULL => heap.get(objref).is_extensible(),
+        _ => false,
+    };
+    Ok(Interpreted::from(extensible))
+}
+
+#[allow(non_snake_case)]
+fn object_object_isSealed(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let sealed = match call.arg_value(0, heap)? {
+        JSValue::Ref(objref) if objref != Heap::NULL => heap.get(objref).is_sealed(),
+        _ => true,
+    };
+    Ok(Interpreted::from(sealed))
+}
+
+#[allow(non_snake_case)]
+fn object_object_preventExtensions(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let value = call.arg_value(0, heap)?;
+    if let JSValue::Ref(objref) = value {
+        if objref != Heap::NULL {
+            heap.get_mut(objref).prevent_extensions();
+        }
+    }
+    Ok(Interpreted::from(value))
+}
+
+fn object_object_seal(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let value = call.arg_value(0, heap)?;
+    if let JSValue::Ref(objref) = value {
+        if objref != Heap::NULL {
+            heap.get_mut(objref).seal();
+        }
+    }
+    Ok(Interpreted::from(value))
+}
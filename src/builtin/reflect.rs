@@ -0,0 +1,108 @@
+//! The builtin `Reflect` object.
+
+use crate::error::TypeError;
+use crate::prelude::*;
+use crate::{CallContext, Exception, Heap, Interpreted, JSObject, JSRef, JSResult};
+
+static METHODS: &[(&str, crate::HostFn)] = &[
+    ("apply", reflect_apply),
+    ("deleteProperty", reflect_deleteProperty),
+    ("get", reflect_get),
+    ("has", reflect_has),
+    ("ownKeys", reflect_ownKeys),
+    ("set", reflect_set),
+];
+
+/// `Reflect` methods throw a TypeError instead of coercing a non-object target.
+fn arg_object(call: &CallContext, index: usize, heap: &mut Heap) -> JSResult<JSRef> {
+    let value = call.arg_value(index, heap)?;
+    value
+        .to_ref()
+        .map_err(|_| Exception::type_error(TypeError::NOT_AN_OBJECT, value))
+}
+
+/// The property named by the second argument of `target`.
+fn arg_member(call: &CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let objref = arg_object(call, 0, heap)?;
+    let propname = call.arg_value(1, heap)?.stringify(heap)?;
+    Ok(Interpreted::member(objref, propname.as_str()))
+}
+
+fn reflect_apply(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let funcref = arg_object(&call, 0, heap)?;
+    let this_ref = call.arg_value(1, heap)?.objectify(heap);
+    let args_value = call.arg_value(2, heap)?;
+    let args_ref = (args_value.to_ref())
+        .map_err(|_| Exception::type_error(TypeError::NOT_ARRAYLIKE, args_value.clone()))?;
+    // any array-like: `length` and indexed properties, holes are `undefined`
+    let arguments = (super::array::arraylike_storage(args_ref, heap)?.into_iter())
+        .map(|value| Interpreted::from(value.unwrap_or(JSValue::Undefined)))
+        .collect::<Vec<_>>();
+
+    heap.execute(
+        funcref,
+        CallContext::from(arguments)
+            .with_this(this_ref)
+            .with_name(call.method_name),
+    )
+}
+
+#[allow(non_snake_case)]
+fn reflect_deleteProperty(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let member = arg_member(&call, heap)?;
+    Ok(Interpreted::from(member.delete(heap).is_ok()))
+}
+
+fn reflect_get(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let member = arg_member(&call, heap)?;
+    Ok(Interpreted::from(member.to_value(heap)?))
+}
+
+fn reflect_has(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let objref = arg_object(&call, 0, heap)?;
+    let propname = call.arg_value(1, heap)?.stringify(heap)?;
+    let found = heap.get(objref).lookup_value(&propname, heap).is_some();
+    Ok(Interpreted::from(found))
+}
+
+#[allow(non_snake_case)]
+fn reflect_ownKeys(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let objref = arg_object(&call, 0, heap)?;
+    let object = heap.get(objref);
+
+    let mut keys: Vec<JSValue> = match object.as_array() {
+        Some(array) => (0..array.storage.len())
+            .filter(|&i| array.get(i).is_some())
+            .map(|i| JSValue::from(i.to_string()))
+            .collect(),
+        None => vec![],
+    };
+    let names = object.ordered_properties().map(|(name, _)| name.clone());
+    keys.extend(names.map(JSValue::from));
+
+    let keys_ref = heap.alloc(JSObject::from_array(keys));
+    Ok(Interpreted::from(keys_ref))
+}
+
+fn reflect_set(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let member = arg_member(&call, heap)?;
+    let value = call.arg_value(2, heap)?;
+    match member.put_value(value, heap) {
+        Ok(()) => Ok(Interpreted::from(true)),
+        // exceptions from setters and interrupts propagate
+        Err(e) => crate::error::ignore_set_readonly(e).map(|()| Interpreted::from(false)),
+    }
+}
+
+pub fn init(heap: &mut Heap) -> JSResult<()> {
+    let mut reflect_object = JSObject::new();
+    for &(name, func) in METHODS.iter() {
+        let func = heap.alloc_func(func);
+        reflect_object.set_hidden(name, func)?;
+    }
+
+    let reflect_ref = heap.alloc(reflect_object);
+    heap.get_mut(Heap::GLOBAL)
+        .set_hidden("Reflect", reflect_ref)?;
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::error::{RangeError, TypeError};
 use crate::prelude::*;
 use crate::{
     object::HostClass, CallContext, Exception, Heap, Interpreted, JSObject, JSRef, JSResult,
@@ -7,17 +8,29 @@ pub static CLASS: HostClass = HostClass {
     name: "String",
     constructor: string_constructor,
     methods: &[
+        ("at", string_proto_at),
         ("charAt", string_proto_charAt),
         ("charCodeAt", string_proto_charCodeAt),
+        ("concat", string_proto_concat),
         ("indexOf", string_proto_indexOf),
+        ("lastIndexOf", string_proto_lastIndexOf),
+        ("normalize", string_proto_normalize),
+        ("padEnd", string_proto_padEnd),
+        ("padStart", string_proto_padStart),
+        ("repeat", string_proto_repeat),
         ("replace", string_proto_replace),
         ("slice", string_proto_slice),
+        ("split", string_proto_split),
         ("substr", string_proto_substr),
         ("toString", string_proto_valueOf),
         ("valueOf", string_proto_valueOf),
         ("toLowerCase", string_proto_toLowerCase),
+        ("toUpperCase", string_proto_toUpperCase),
+        // there is no `Intl`, locales are ignored
+        ("toLocaleLowerCase", string_proto_toLowerCase),
+        ("toLocaleUpperCase", string_proto_toUpperCase),
     ],
-    static_methods: &[],
+    static_methods: &[("raw", string_object_raw)],
 };
 
 fn string_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
@@ -35,6 +48,34 @@ fn string_constructor(call: CallContext, heap: &mut Heap) -> JSResult<Interprete
     Ok(Interpreted::VOID)
 }
 
+/// `String.raw(strings, ...substitutions)` interleaves `strings.raw` with `substitutions`,
+/// e.g. as a template tag: ``String.raw`a\n${1}` `` is `"a\\n1"`.
+fn string_object_raw(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let to_object = |value: JSValue, heap: &mut Heap| match value {
+        JSValue::Undefined | JSValue::Ref(Heap::NULL) => {
+            Err(Exception::type_error(TypeError::NOT_AN_OBJECT, value))
+        }
+        value => Ok(value.objectify(heap)),
+    };
+    let strings_ref = to_object(call.arg_value(0, heap)?, heap)?;
+    let raw = Interpreted::member(strings_ref, "raw").to_value(heap)?;
+    let raw_ref = to_object(raw, heap)?;
+    let chunks = super::array::arraylike_storage(raw_ref, heap)?;
+
+    let mut s = String::new();
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        if i > 0 {
+            if let Some(substitution) = call.arguments.get(i) {
+                let substitution = substitution.to_value(heap)?;
+                s.push_str(substitution.stringify(heap)?.as_str());
+            }
+        }
+        let chunk = chunk.unwrap_or(JSValue::Undefined);
+        s.push_str(chunk.stringify(heap)?.as_str());
+    }
+    Ok(Interpreted::from(s))
+}
+
 impl Heap {
     fn ref_to_string(&mut self, href: JSRef) -> JSResult<JSString> {
         match self.get(href).to_primitive() {
@@ -51,6 +92,23 @@ fn string_proto_valueOf(call: CallContext, heap: &mut Heap) -> JSResult<Interpre
     Ok(Interpreted::from(strval))
 }
 
+fn string_proto_at(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let s = heap.ref_to_string(call.this_ref)?;
+    let strlen = s.chars().count() as i64;
+    let index = match call.arg_as_number(0, heap)?.unwrap_or(0) {
+        i if i < 0 => i + strlen,
+        i => i,
+    };
+    if index < 0 {
+        return Ok(Interpreted::VOID);
+    }
+    let result = match s.chars().nth(index as usize) {
+        Some(c) => Interpreted::from(c.to_string()),
+        None => Interpreted::VOID,
+    };
+    Ok(result)
+}
+
 #[allow(non_snake_case)]
 fn string_proto_charAt(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
     let index = call.arg_as_number(0, heap)?.unwrap_or(0);
@@ -73,6 +131,88 @@ fn string_proto_charCodeAt(call: CallContext, heap: &mut Heap) -> JSResult<Inter
     Ok(Interpreted::from(result))
 }
 
+fn string_proto_concat(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let mut result = heap.ref_to_string(call.this_ref)?.to_string();
+    for i in 0..call.arguments.len() {
+        let arg = call.arg_value(i, heap)?;
+        result.push_str(arg.stringify(heap)?.as_str());
+    }
+    Ok(Interpreted::from(result))
+}
+
+/// `.normalize(form)`: `"NFC"` by default.
+fn string_proto_normalize(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    use unicode_normalization::UnicodeNormalization;
+
+    let s = heap.ref_to_string(call.this_ref)?;
+    let form = match call.arg_value(0, heap)? {
+        JSValue::Undefined => JSString::from("NFC"),
+        form => form.stringify(heap)?,
+    };
+    let normalized: String = match form.as_str() {
+        "NFC" => s.nfc().collect(),
+        "NFD" => s.nfd().collect(),
+        "NFKC" => s.nfkc().collect(),
+        "NFKD" => s.nfkd().collect(),
+        _ => {
+            let tag = RangeError::INVALID_NORMALIZATION_FORM;
+            return Err(Exception::range_error(tag, form));
+        }
+    };
+    Ok(Interpreted::from(normalized))
+}
+
+fn string_proto_repeat(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let s = heap.ref_to_string(call.this_ref)?;
+    let count = call.arg_value(0, heap)?.to_number(heap)?;
+    let count = if count.is_nan() { 0.0 } else { count.trunc() };
+    if count < 0.0 || count.is_infinite() {
+        return Err(Exception::range_error(RangeError::INVALID_COUNT, count));
+    }
+    let strlen = s.chars().count();
+    if strlen == 0 {
+        return Ok(Interpreted::from(""));
+    }
+    // check before allocating anything
+    let length = (strlen as f64) * count;
+    heap.check_string_length(length.min(usize::MAX as f64) as usize)?;
+
+    Ok(Interpreted::from(s.as_str().repeat(count as usize)))
+}
+
+/// `padStart()`/`padEnd()`: the filler to add to `s` to make it `targetLength` long.
+fn string_padding(s: &str, call: &CallContext, heap: &mut Heap) -> JSResult<String> {
+    let target = call.arg_value(0, heap)?.to_number(heap)?;
+    let target = if target.is_nan() { 0.0 } else { target.trunc() };
+    let filler = match call.arg_value(1, heap)? {
+        JSValue::Undefined => JSString::from(" "),
+        value => value.stringify(heap)?,
+    };
+
+    let strlen = s.chars().count();
+    if target <= strlen as f64 || filler.as_str().is_empty() {
+        return Ok(String::new());
+    }
+    heap.check_string_length(target.min(usize::MAX as f64) as usize)?;
+
+    let padlen = target as usize - strlen;
+    Ok(filler.chars().cycle().take(padlen).collect())
+}
+
+#[allow(non_snake_case)]
+fn string_proto_padEnd(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let s = heap.ref_to_string(call.this_ref)?;
+    let padding = string_padding(s.as_str(), &call, heap)?;
+    Ok(Interpreted::from(format!("{}{}", s, padding)))
+}
+
+#[allow(non_snake_case)]
+fn string_proto_padStart(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let s = heap.ref_to_string(call.this_ref)?;
+    let padding = string_padding(s.as_str(), &call, heap)?;
+    Ok(Interpreted::from(format!("{}{}", padding, s)))
+}
+
 fn string_proto_slice(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
     let s = heap.ref_to_string(call.this_ref)?;
     let strlen = s.chars().count() as i64;
@@ -97,6 +237,38 @@ fn string_proto_slice(call: CallContext, heap: &mut Heap) -> JSResult<Interprete
     Ok(Interpreted::from(substr))
 }
 
+/// `.split(separator, limit)`: `limit` is ToUint32, an empty separator gives characters.
+/// TODO: a `RegExp` separator, with its capture groups included
+fn string_proto_split(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let s = heap.ref_to_string(call.this_ref)?;
+    let limit = match call.arg_value(1, heap)? {
+        JSValue::Undefined => u32::MAX,
+        limit => match limit.to_number(heap)? {
+            n if n.is_finite() => n.trunc().rem_euclid(4294967296.0) as u32,
+            _ => 0,
+        },
+    } as usize;
+    let separator = match call.arg_value(0, heap)? {
+        JSValue::Undefined => None,
+        separator => Some(separator.stringify(heap)?),
+    };
+
+    let parts = match separator {
+        _ if limit == 0 => vec![],
+        None => vec![JSValue::from(s)],
+        Some(separator) if separator.is_empty() => (s.chars())
+            .take(limit)
+            .map(|c| JSValue::from(c.to_string()))
+            .collect(),
+        Some(separator) => (s.split(separator.as_str()))
+            .take(limit)
+            .map(JSValue::from)
+            .collect(),
+    };
+    let array_ref = heap.alloc(JSObject::from_array(parts));
+    Ok(Interpreted::from(array_ref))
+}
+
 fn string_proto_substr(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
     let s = heap.ref_to_string(call.this_ref)?;
     let strlen = s.chars().count() as i64;
@@ -151,6 +323,38 @@ fn string_proto_indexOf(call: CallContext, heap: &mut Heap) -> JSResult<Interpre
     Ok(Interpreted::from(char_start + char_index as i64))
 }
 
+#[allow(non_snake_case)]
+fn string_proto_lastIndexOf(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let heystack = heap.ref_to_string(call.this_ref)?;
+    let strlen = heystack.chars().count() as i64; // COSTLY
+
+    let needle = call.arg_value(0, heap)?.stringify(heap)?;
+
+    // unlike indexOf, NaN means "search from the end"
+    let position = call.arg_value(1, heap)?.to_number(heap)?;
+    let char_start = match position {
+        p if p.is_nan() => strlen,
+        p if p < 0.0 => 0,
+        p if p > strlen as f64 => strlen,
+        p => p as i64,
+    };
+    if needle.is_empty() {
+        return Ok(Interpreted::from(char_start));
+    }
+
+    // COSTLY: try every char offset up to `char_start`, remember the last match
+    let found = (heystack.char_indices())
+        .take(char_start as usize + 1)
+        .enumerate()
+        .filter(|(_, (byte_index, _))| heystack[*byte_index..].starts_with(needle.as_str()))
+        .last();
+    let result = match found {
+        Some((char_index, _)) => char_index as i64,
+        None => -1,
+    };
+    Ok(Interpreted::from(result))
+}
+
 fn string_proto_replace(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
     let string = heap.ref_to_string(call.this_ref)?;
 
@@ -200,3 +404,77 @@ fn string_proto_toLowerCase(call: CallContext, heap: &mut Heap) -> JSResult<Inte
     let string = heap.ref_to_string(call.this_ref)?;
     Ok(Interpreted::from(string.to_lowercase()))
 }
+
+#[allow(non_snake_case)]
+fn string_proto_toUpperCase(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let string = heap.ref_to_string(call.this_ref)?;
+    Ok(Interpreted::from(string.to_uppercase()))
+}
+
+/// The hidden prototype of string iterators, kept on `String.prototype`.
+const STRING_ITERATOR_PROTO: &str = "[[StringIterator]]";
+
+/// Makes strings iterable by code points.
+pub fn init_iterator(heap: &mut Heap) -> JSResult<()> {
+    let next_ref = heap.alloc_func(string_iterator_next);
+    let mut iterator_proto = JSObject::new();
+    iterator_proto.set_hidden("next", next_ref)?;
+    let iterator_proto_ref = heap.alloc(iterator_proto);
+    let iterator_ref = heap.alloc_func(string_proto_iterator);
+
+    let string_proto = heap.get_mut(Heap::STRING_PROTO);
+    string_proto.set_system(STRING_ITERATOR_PROTO, iterator_proto_ref)?;
+    string_proto.set_system(Heap::SYMBOL_ITERATOR, iterator_ref)
+}
+
+/// `String.prototype[Symbol.iterator]()`
+fn string_proto_iterator(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let s = heap.ref_to_string(call.this_ref)?;
+    let iterator_proto = (heap.get(Heap::STRING_PROTO))
+        .get_own_value(STRING_ITERATOR_PROTO)
+        .expect("no string iterator prototype")
+        .to_ref()?;
+
+    let mut iterator = JSObject::new();
+    iterator.proto = iterator_proto;
+    iterator.set_system("[[iterated]]", s)?;
+    // a byte offset of the next code point
+    iterator.set_system("[[index]]", 0)?;
+    Ok(Interpreted::from(heap.alloc(iterator)))
+}
+
+/// `next()` of string iterators: `{value, done}`, a value is one code point.
+fn string_iterator_next(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let iterator = heap.get(call.this_ref);
+    let (s, index) = match (
+        iterator.get_own_value("[[iterated]]"),
+        iterator.get_own_value("[[index]]"),
+    ) {
+        (Some(JSValue::String(s)), Some(JSValue::Number(index))) => (s, index as usize),
+        (Some(JSValue::Undefined), _) => {
+            return super::array::iterator_result(JSValue::Undefined, true, heap)
+        }
+        _ => {
+            let what = "String Iterator";
+            return Err(Exception::attr_type_error(
+                TypeError::INSTANCE_REQUIRED,
+                call.this_ref,
+                what,
+            ));
+        }
+    };
+
+    let iterator = heap.get_mut(call.this_ref);
+    match s.as_str()[index..].chars().next() {
+        Some(c) => {
+            let index = (index + c.len_utf8()) as f64;
+            iterator.set_even_nonwritable("[[index]]", index)?;
+            super::array::iterator_result(JSValue::from(c.to_string()), false, heap)
+        }
+        None => {
+            // a finished iterator forgets its string
+            iterator.set_even_nonwritable("[[iterated]]", JSValue::Undefined)?;
+            super::array::iterator_result(JSValue::Undefined, true, heap)
+        }
+    }
+}
@@ -2,7 +2,7 @@
 use std::io;
 
 use crate::prelude::*;
-use crate::{ast::Identifier, Interpreted, JSValue, JSON};
+use crate::{ast::Identifier, function::TailCall, Heap, Interpreted, JSValue, JSON};
 
 pub type JSResult<T> = Result<T, Exception>;
 
@@ -22,6 +22,13 @@ pub enum Exception {
 
     /// TypeError
     Type(TypeError),
+
+    /// RangeError
+    Range(RangeError),
+
+    /// the script went over [`crate::Limits::max_steps`] after this many steps;
+    /// `catch` cannot stop it, `finally` blocks still run
+    Interrupted(u64),
 }
 
 // TODO: impl Display for Exception
@@ -36,10 +43,42 @@ impl Exception {
         Exception::attr_type_error(TypeError::INSTANCE_REQUIRED, arg, of)
     }
 
+    /// The JavaScript error class of an internal exception, e.g. `"TypeError"`.
+    pub(crate) fn error_class(&self) -> &'static str {
+        match self {
+            Exception::Type(_) => "TypeError",
+            Exception::Range(_) => "RangeError",
+            Exception::Reference(_) => "ReferenceError",
+            Exception::Syntax(_) => "SyntaxError",
+            _ => "Error",
+        }
+    }
+
+    /// The message of an internal exception for `Error.prototype.message`,
+    /// e.g. `x is not callable`; thrown values are shown as they are.
+    pub(crate) fn message(&self, heap: &Heap) -> String {
+        match self {
+            Exception::Type(err) => err.message(heap),
+            Exception::Range(err) => err.message(heap),
+            Exception::Reference(err) => err.message(heap),
+            Exception::Syntax(err) => err.to_string(),
+            Exception::UserThrown(value) => heap.inspect(value),
+            Exception::Interrupted(steps) => format!("Interrupted after {} steps", steps),
+            Exception::Jump(_) => format!("{:?}", self),
+        }
+    }
+
     pub(crate) fn no_loop_for_continue_label(label: Identifier) -> Self {
         Self::Syntax(ParseError::ContinueLabelNotALoop(label))
     }
 
+    pub(crate) fn misplaced_spread() -> Self {
+        Self::Syntax(ParseError::want(
+            "an array element or an argument",
+            JSON::from("..."),
+        ))
+    }
+
     pub(crate) fn no_reference<Id>(id: Id) -> Self
     where
         Identifier: From<Id>,
@@ -81,6 +120,16 @@ impl Exception {
             attr: JSString::from(""),
         })
     }
+
+    pub(crate) fn range_error<V>(tag: &'static str, what: V) -> Exception
+    where
+        Interpreted: From<V>,
+    {
+        Self::Range(RangeError {
+            tag,
+            value: Interpreted::from(what),
+        })
+    }
 }
 
 impl From<ParseError> for Exception {
@@ -98,10 +147,12 @@ impl From<Exception> for io::Error {
     }
 }
 
+/// Assignments to read-only properties or new properties of non-extensible objects
+/// fail silently in sloppy mode.
 pub fn ignore_set_readonly(e: Exception) -> JSResult<()> {
     match e {
         Exception::Type(TypeError {
-            tag: TypeError::SET_READONLY,
+            tag: TypeError::SET_READONLY | TypeError::NOT_EXTENSIBLE,
             ..
         }) => Ok(()),
         _ => Err(e),
@@ -111,10 +162,32 @@ pub fn ignore_set_readonly(e: Exception) -> JSResult<()> {
 #[derive(Debug, PartialEq)]
 pub enum Jump {
     Return(Interpreted),
+    /// `return f(...)`: the call is made by [`Closure::call`](crate::function::Closure::call)
+    /// after the returning function's frame is gone.
+    TailCall(Box<TailCall>),
     Break(Option<Identifier>),
     Continue(Option<Identifier>),
 }
 
+/// The offending name or value of an internal exception as it appears in its message.
+fn describe(what: &Interpreted, heap: &Heap) -> String {
+    match what {
+        Interpreted::Member { name, .. } if name.as_str().is_empty() => "(anonymous)".into(),
+        Interpreted::Member { name, .. } => name.to_string(),
+        Interpreted::Value(JSValue::String(s)) => format!("\"{}\"", s),
+        Interpreted::Value(value) => heap.inspect(value),
+    }
+}
+
+/// Capitalized tags are whole messages, the others follow the name or the value:
+/// `x is not callable`.
+fn tag_message(tag: &str, subject: String) -> String {
+    match tag.starts_with(char::is_uppercase) {
+        true => tag.to_string(),
+        false => format!("{} {}", subject, tag),
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct TypeError {
     tag: &'static str,
@@ -123,16 +196,70 @@ pub struct TypeError {
 }
 
 impl TypeError {
-    pub const SET_READONLY: &'static str = "cannot set a readonly property";
-    pub const NONCONFIGURABLE_PROPERTY: &'static str = "the property is not configuratble";
-    pub const CANNOT_GET_PROPERTY: &'static str = "property is not gettable";
-    pub const CANNOT_SET_PROPERTY: &'static str = "property is not settable";
-    pub const CONST_ASSIGN: &'static str = "cannot assign to const";
-    pub const NOT_CALLABLE: &'static str = "not callable";
-    pub const NOT_ARRAYLIKE: &'static str = "not array-like";
-    pub const INSTANCE_REQUIRED: &'static str = "an instance required";
-    pub const INVALID_DESCRIPTOR: &'static str = "invalid descriptor";
-    pub const INVALID_PROTO: &'static str = "invalid prototype";
+    pub const SET_READONLY: &'static str = "is a read-only property";
+    pub const NOT_EXTENSIBLE: &'static str = "cannot be added, the object is not extensible";
+    pub const NONCONFIGURABLE_PROPERTY: &'static str = "is not a configurable property";
+    pub const CANNOT_GET_PROPERTY: &'static str = "cannot be read";
+    pub const CANNOT_SET_PROPERTY: &'static str = "cannot be set";
+    pub const CONST_ASSIGN: &'static str = "is a constant, it cannot be assigned";
+    pub const NOT_CALLABLE: &'static str = "is not callable";
+    pub const NOT_CONSTRUCTOR: &'static str = "is not a constructor";
+    pub const NOT_ARRAYLIKE: &'static str = "is not array-like";
+    pub const REDUCE_EMPTY: &'static str = "Reduce of empty array with no initial value";
+    pub const NOT_ITERABLE: &'static str = "is not iterable";
+    pub const NOT_AN_OBJECT: &'static str = "is not an object";
+    pub const NOT_A_SCOPE: &'static str = "is not a scope";
+    pub const INSTANCE_REQUIRED: &'static str = "instance is required";
+    pub const INVALID_DESCRIPTOR: &'static str = "is not a valid property descriptor";
+    pub const INVALID_PROTO: &'static str = "is not a valid prototype";
+    pub const EVAL_DISABLED: &'static str = "cannot be evaluated, eval() is disabled in a sandbox";
+    pub const NO_PARSER: &'static str = "No parser to evaluate code at runtime";
+    pub const CIRCULAR_JSON: &'static str = "Converting circular structure to JSON";
+    pub const NOT_CLONEABLE: &'static str = "could not be cloned";
+    pub const NO_PRIMITIVE: &'static str = "Cannot convert object to primitive value";
+    pub const IN_NOT_OBJECT: &'static str = "Cannot use 'in' operator to search in a non-object";
+    pub const INSTANCEOF_NOT_CALLABLE: &'static str =
+        "Right-hand side of 'instanceof' is not callable";
+    pub const NEW_REQUIRED: &'static str = "Constructor requires 'new'";
+    pub const ILLEGAL_CONSTRUCTOR: &'static str = "Illegal constructor";
+    pub const BIGINT_MIX: &'static str =
+        "Cannot mix BigInt and other types, use explicit conversions";
+    pub const BIGINT_TO_NUMBER: &'static str = "Cannot convert a BigInt value to a number";
+    pub const BIGINT_TO_JSON: &'static str = "Do not know how to serialize a BigInt";
+
+    /// Property errors are about `attr`, the others about the value.
+    fn message(&self, heap: &Heap) -> String {
+        let subject = match self.attr.as_str() {
+            "" => describe(&self.value, heap),
+            attr => attr.to_string(),
+        };
+        tag_message(self.tag, subject)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RangeError {
+    tag: &'static str,
+    value: Interpreted,
+}
+
+impl RangeError {
+    pub const INVALID_RADIX: &'static str = "radix must be between 2 and 36";
+    pub const INVALID_PRECISION: &'static str = "precision must be between 1 and 100";
+    pub const INVALID_DIGITS: &'static str = "fraction digits must be between 0 and 100";
+    pub const INVALID_LENGTH: &'static str = "Invalid array length";
+    pub const INVALID_STRING_LENGTH: &'static str = "Invalid string length";
+    pub const INVALID_COUNT: &'static str = "Invalid count value";
+    pub const INVALID_INDEX: &'static str = "Invalid index";
+    pub const INVALID_NORMALIZATION_FORM: &'static str =
+        "The normalization form should be one of NFC, NFD, NFKC, NFKD";
+    pub const DIVISION_BY_ZERO: &'static str = "Division by zero";
+    pub const NEGATIVE_EXPONENT: &'static str = "Exponent must be non-negative";
+    pub const BIGINT_TOO_BIG: &'static str = "Maximum BigInt size exceeded";
+
+    fn message(&self, heap: &Heap) -> String {
+        format!("{}: {}", self.tag, describe(&self.value, heap))
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -143,8 +270,16 @@ pub struct ReferenceError {
 }
 
 impl ReferenceError {
-    pub const NOT_FOUND: &'static str = "Reference not found";
-    pub const NOT_OBJECT: &'static str = "Reference is not an object";
+    pub const NOT_FOUND: &'static str = "is not defined";
+    pub const NOT_OBJECT: &'static str = "is not an object";
+
+    fn message(&self, heap: &Heap) -> String {
+        let subject = match self.to.as_str() {
+            "" => describe(&self.value, heap),
+            name => name.to_string(),
+        };
+        tag_message(self.tag, subject)
+    }
 
     pub fn not_found<Id>(id: Id) -> Self
     where
@@ -167,6 +302,10 @@ pub enum ParseError {
     BindingRedeclared {},
     ForInMultipleVar(),
     ContinueLabelNotALoop(Identifier),
+    UndefinedLabel(Identifier),
+    IllegalBreak(),
+    IllegalContinue(),
+    IllegalReturn(),
 }
 
 impl ParseError {
@@ -187,6 +326,30 @@ impl ParseError {
     }
 }
 
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidJSON { err } => write!(f, "{}", err.trim()),
+            ParseError::ObjectWithout { attr, value } => write!(f, "no '{}' in {}", attr, value),
+            ParseError::UnexpectedValue { want, value } => {
+                write!(f, "expected {}, got {}", want, value)
+            }
+            ParseError::UnknownNodeType { value } => write!(f, "unknown node type: {}", value),
+            ParseError::BindingRedeclared {} => write!(f, "a binding is declared twice"),
+            ParseError::ForInMultipleVar() => {
+                write!(f, "only one variable can be declared in a for-in head")
+            }
+            ParseError::ContinueLabelNotALoop(label) => {
+                write!(f, "label '{}' is not a loop", label.as_str())
+            }
+            ParseError::UndefinedLabel(label) => write!(f, "undefined label '{}'", label.as_str()),
+            ParseError::IllegalBreak() => write!(f, "illegal break statement"),
+            ParseError::IllegalContinue() => write!(f, "illegal continue statement"),
+            ParseError::IllegalReturn() => write!(f, "illegal return statement"),
+        }
+    }
+}
+
 impl From<&str> for ParseError {
     fn from(err: &str) -> Self {
         // This assumes nodejs-formatted Esprima error output.
@@ -1,8 +1,8 @@
 use crate::prelude::*;
 
 use crate::{
-    ast, source, Exception, Heap, Interpretable, Interpreted, JSObject, JSRef, JSResult, JSValue,
-    Jump,
+    ast, object::ObjectValue, source, Exception, Heap, Interpretable, Interpreted, JSObject, JSRef,
+    JSResult, JSValue, Jump,
 };
 
 /// Call context information (e.g. arguments) for [`Heap::execute()`].
@@ -22,7 +22,7 @@ use crate::{
 ///
 /// let arguments = vec![ Interpreted::from("42") ];
 /// let result = heap.execute( func_ref, CallContext::from(arguments)).expect("execute");
-/// let result = result.to_value(&heap).unwrap();
+/// let result = result.to_value(&mut heap).unwrap();
 ///
 /// assert_eq!(result, JSValue::from(42));
 /// ```
@@ -32,6 +32,10 @@ pub struct CallContext {
     pub method_name: JSString,
     pub arguments: Vec<Interpreted>,
     pub loc: Option<Box<source::Location>>,
+    /// Is this a direct `eval(...)` call, i.e. should `eval` see the caller's scope?
+    pub direct_eval: bool,
+    /// `new.target`: the constructor if called via `new`.
+    pub new_target: Option<JSRef>,
 }
 
 impl CallContext {
@@ -52,7 +56,7 @@ impl CallContext {
             .to_value(heap)
     }
 
-    pub fn arg_as_number(&self, argnum: usize, heap: &Heap) -> JSResult<Option<i64>> {
+    pub fn arg_as_number(&self, argnum: usize, heap: &mut Heap) -> JSResult<Option<i64>> {
         let arg = match self.arguments.get(argnum) {
             Some(arg) => arg.to_value(heap)?,
             None => return Ok(None),
@@ -68,6 +72,8 @@ impl From<Vec<Interpreted>> for CallContext {
             method_name: JSString::from(""),
             loc: None,
             this_ref: Heap::NULL,
+            direct_eval: false,
+            new_target: None,
         }
     }
 }
@@ -100,6 +106,27 @@ impl fmt::Debug for HostFunc {
     }
 }
 
+/// A call in tail position, see [`Jump::TailCall`].
+/// The arguments are evaluated before the caller's frame is gone.
+#[derive(Debug, PartialEq)]
+pub struct TailCall {
+    pub func_ref: JSRef,
+    pub this_ref: JSRef,
+    pub method_name: JSString,
+    pub arguments: Vec<JSValue>,
+}
+
+impl TailCall {
+    pub fn into_call(self) -> (JSRef, CallContext) {
+        let arguments: Vec<Interpreted> =
+            self.arguments.into_iter().map(Interpreted::from).collect();
+        let call = CallContext::from(arguments)
+            .with_this(self.this_ref)
+            .with_name(self.method_name);
+        (self.func_ref, call)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Closure {
     pub function: Rc<ast::Function>,
@@ -108,6 +135,21 @@ pub struct Closure {
 
 impl Closure {
     pub fn call(&self, call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+        let mut result = self.call_body(call, heap);
+        // Tail calls reuse this native stack frame instead of nesting.
+        while let Err(Exception::Jump(Jump::TailCall(tail))) = result {
+            let (func_ref, mut call) = tail.into_call();
+            call.loc = heap.loc.clone();
+            result = match &heap.get(func_ref).value {
+                ObjectValue::Closure(closure) => closure.clone().call_body(call, heap),
+                _ => heap.execute(func_ref, call),
+            };
+        }
+        result
+    }
+
+    /// Runs the function body, a `return f(...)` in it comes out as [`Jump::TailCall`].
+    fn call_body(&self, call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
         let result = heap.enter_new_scope(call.this_ref, self.captured_scope, |heap| {
             // `arguments`
             let argv = (call.arguments.iter())
@@ -116,6 +158,13 @@ impl Closure {
             let arguments_ref = heap.alloc(JSObject::from_array(argv));
             heap.scope_mut().set_nonconf("arguments", arguments_ref)?;
 
+            // `new.target`
+            let new_target = match call.new_target {
+                Some(target_ref) => JSValue::from(target_ref),
+                None => JSValue::Undefined,
+            };
+            heap.scope_mut().set_system(Heap::NEW_TARGET, new_target)?;
+
             // set each argument
             for (i, param) in self.function.params.iter().enumerate() {
                 let value = (call.arguments.get(i))
@@ -1,15 +1,18 @@
+use core::cell::RefCell;
+
 use crate::ast::{FunctionDeclaration, Identifier};
-use crate::error::TypeError;
+use crate::builtin::console::ConsoleState;
+use crate::error::{RangeError, TypeError};
 use crate::function::{CallContext, HostFn};
-use crate::object::HostClass;
+use crate::object::{is_valid_identifier, Access, Content, HostClass, Property};
 use crate::prelude::*;
 use crate::{
     builtin, object::ObjectValue, source, Exception, Interpretable, Interpreted, JSObject,
-    JSResult, JSValue, JSON,
+    JSResult, JSValue, Program, JSON,
 };
 
 /// A heap reference: a Heap index.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct JSRef(usize);
 
 impl JSRef {
@@ -18,6 +21,8 @@ impl JSRef {
     }
 
     pub fn isinstance(&self, constructor: JSRef, heap: &Heap) -> JSResult<bool> {
+        // `instanceof` a bound function checks its target
+        let constructor = builtin::function::constructor_of(constructor, heap);
         let protoval = heap.get(constructor).get_own_value("prototype");
         let protoval =
             protoval.ok_or_else(|| Exception::type_error(TypeError::NOT_CALLABLE, constructor))?;
@@ -48,11 +53,211 @@ impl JSRef {
     }
 }
 
+/// `HeapBuilder` selects optional groups of builtins before making a [`Heap`].
+/// Everything is enabled by default, i.e. `HeapBuilder::new().build()` is `Heap::new()`.
+///
+/// ```
+/// # use sljs::{HeapBuilder, JSValue};
+/// let heap = HeapBuilder::new().console(false).math(false).build();
+/// assert!(heap.lookup_var("console").is_none());
+/// assert!(heap.lookup_var("JSON").is_some());
+/// ```
+#[derive(Debug, Clone)]
+pub struct HeapBuilder {
+    pub(crate) console: bool,
+    pub(crate) math: bool,
+    pub(crate) json: bool,
+    pub(crate) error_types: bool,
+    pub(crate) process: bool,
+    pub(crate) limits: Limits,
+    pub(crate) global_proto: Option<JSObject>,
+    pub(crate) object_proto_in_globals: bool,
+}
+
+impl Default for HeapBuilder {
+    fn default() -> Self {
+        HeapBuilder {
+            console: true,
+            math: true,
+            json: true,
+            error_types: true,
+            process: true,
+            limits: Limits::default(),
+            global_proto: None,
+            object_proto_in_globals: true,
+        }
+    }
+}
+
+impl HeapBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `console.log()` and alike, see [`Heap::set_stdout`]
+    pub fn console(mut self, enabled: bool) -> Self {
+        self.console = enabled;
+        self
+    }
+
+    /// the `Math` object
+    pub fn math(mut self, enabled: bool) -> Self {
+        self.math = enabled;
+        self
+    }
+
+    /// the `JSON` object
+    pub fn json(mut self, enabled: bool) -> Self {
+        self.json = enabled;
+        self
+    }
+
+    /// `TypeError`, `RangeError` and other subclasses of `Error`
+    pub fn error_types(mut self, enabled: bool) -> Self {
+        self.error_types = enabled;
+        self
+    }
+
+    /// the nodejs-like `process` object with the host's `argv` and `env`, which `NodejsParser`
+    /// sets up; keep it off for untrusted scripts, like a sandboxed `Runtime` does
+    pub fn process(mut self, enabled: bool) -> Self {
+        self.process = enabled;
+        self
+    }
+
+    /// Sizes of strings and arrays, see [`Limits`]
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// A custom prototype of the global object, `build()` allocates it.
+    /// Its own prototype is `Object.prototype`, unless [`HeapBuilder::object_proto_in_globals`]
+    /// is off:
+    /// ```
+    /// # use sljs::{Heap, HeapBuilder, JSObject, JSValue};
+    /// let mut proto = JSObject::new();
+    /// proto.set_property("inherited", JSValue::from(42)).unwrap();
+    /// let heap = HeapBuilder::new().global_proto(proto).build();
+    /// assert!(heap.lookup_protochain(Heap::GLOBAL, "inherited").is_some());
+    /// assert!(heap.lookup_protochain(Heap::GLOBAL, "toString").is_some());
+    /// ```
+    pub fn global_proto(mut self, proto: JSObject) -> Self {
+        self.global_proto = Some(proto);
+        self
+    }
+
+    /// Is `Object.prototype` on the prototype chain of the global object? On by default.
+    /// Without it, global scope does not inherit `toString` and alike:
+    /// ```
+    /// # use sljs::{Heap, HeapBuilder};
+    /// let heap = HeapBuilder::new().object_proto_in_globals(false).build();
+    /// assert!(heap.lookup_protochain(Heap::GLOBAL, "toString").is_none());
+    /// ```
+    pub fn object_proto_in_globals(mut self, enabled: bool) -> Self {
+        self.object_proto_in_globals = enabled;
+        self
+    }
+
+    pub fn build(&self) -> Heap {
+        Heap::new_with_globals(self)
+    }
+}
+
+/// Upper bounds on what a script can allocate in one go, e.g. `"x".repeat(1e9)`.
+/// Exceeding them throws a `RangeError` instead of running out of memory.
+/// ```
+/// # use sljs::{HeapBuilder, Limits};
+/// let limits = Limits { max_string_length: 1000, ..Limits::default() };
+/// let heap = HeapBuilder::new().limits(limits).build();
+/// assert_eq!(heap.limits().max_string_length, 1000);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limits {
+    /// in characters
+    pub max_string_length: usize,
+    /// in elements, including holes
+    pub max_array_length: usize,
+    /// in statements, see [`Heap::steps`]; more is [`Exception::Interrupted`]
+    pub max_steps: Option<u64>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            // the same as V8 on 64-bit platforms
+            max_string_length: (1 << 29) - 24,
+            // arrays are not sparse, so this is much lower than 2^32 - 1
+            max_array_length: 1 << 24,
+            max_steps: None,
+        }
+    }
+}
+
+/// A copy of all heap objects (and so of globals), see [`Heap::snapshot`].
+#[derive(Debug, Clone)]
+pub struct HeapSnapshot {
+    objects: Vec<JSObject>,
+    object_ids: Vec<u64>,
+    next_object_id: u64,
+    microtasks: Vec<JSRef>,
+    console: ConsoleState,
+}
+
+/// A callback for `debugger;` statements, see [`Heap::set_debugger_hook`].
+/// The heap is mutable: reading a property may call its getter.
+pub type DebuggerHook = Box<dyn FnMut(&mut Heap)>;
+
+/// Receives lines of output, see [`Heap::set_stdout`] and [`Heap::set_stderr`].
+pub type OutputSink = Box<dyn FnMut(&str)>;
+
+/// Parses source code at runtime (e.g. for `Function(...)`), see [`Heap::set_parser_hook`].
+pub type ParserHook = Rc<dyn Fn(&str, &mut Heap) -> JSResult<Program>>;
+
 /// Runtime heap
-#[derive(Debug)]
 pub struct Heap {
     objects: Vec<JSObject>,
+    /// allocation ids of `objects`, see [`Heap::object_id`]
+    object_ids: Vec<u64>,
+    next_object_id: u64,
     pub loc: Option<Box<source::Location>>,
+    debugger_hook: Option<DebuggerHook>,
+    parser_hook: Option<ParserHook>,
+    stdout: Option<OutputSink>,
+    stderr: Option<OutputSink>,
+    trace: bool,
+    microtasks: Vec<JSRef>,
+    limits: Limits,
+    /// statements interpreted, see [`Heap::steps`]
+    steps: u64,
+    /// arrays being `.join()`ed at the moment, to break cycles
+    pub(crate) joining: Vec<JSRef>,
+    /// `console.group()` depth and `console.count()` counters
+    pub(crate) console: ConsoleState,
+    /// may a parser set up `process`? See [`HeapBuilder::process`]
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) process: bool,
+}
+
+impl fmt::Debug for Heap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Heap")
+            .field("objects", &self.objects)
+            .field("object_ids", &self.object_ids)
+            .field("next_object_id", &self.next_object_id)
+            .field("loc", &self.loc)
+            .field("debugger_hook", &self.debugger_hook.is_some())
+            .field("parser_hook", &self.parser_hook.is_some())
+            .field("stdout", &self.stdout.is_some())
+            .field("stderr", &self.stderr.is_some())
+            .field("trace", &self.trace)
+            .field("microtasks", &self.microtasks)
+            .field("limits", &self.limits)
+            .field("steps", &self.steps)
+            .field("joining", &self.joining)
+            .field("console", &self.console)
+            .finish()
+    }
 }
 
 impl Heap {
@@ -65,7 +270,7 @@ impl Heap {
     pub const FUNCTION_PROTO: JSRef = JSRef(3);
     pub const ARRAY_PROTO: JSRef = JSRef(4);
     pub const BOOLEAN_PROTO: JSRef = JSRef(5);
-    //pub const NUMBER_PROTO: JSRef = JSRef(6);
+    pub const NUMBER_PROTO: JSRef = JSRef(6);
     pub const STRING_PROTO: JSRef = JSRef(7);
     pub const REGEXP_PROTO: JSRef = JSRef(8);
 
@@ -77,19 +282,243 @@ impl Heap {
     const CAPTURED_SCOPE: &'static str = "[[captured_scope]]";
     pub(crate) const SCOPE_THIS: &'static str = "[[this]]";
     pub(crate) const SAVED_SCOPE: &'static str = "[[saved_scope]]";
+    pub(crate) const NEW_TARGET: &'static str = "[[new_target]]";
+    pub(crate) const WITH_OBJECT: &'static str = "[[with_object]]";
+    /// Stands in for `Symbol.iterator` until there are symbols: a method making an iterator.
+    pub(crate) const SYMBOL_ITERATOR: &'static str = "[[Symbol.iterator]]";
 
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
+        Self::new_with_globals(&HeapBuilder::default())
+    }
+
+    /// Makes a heap with only the builtins selected in `globals`, see [`HeapBuilder`].
+    pub fn new_with_globals(globals: &HeapBuilder) -> Self {
         let mut objects = Vec::new();
         for _ in 0..Self::USERSTART {
             objects.push(JSObject::new());
         }
 
-        let mut heap = Heap { objects, loc: None };
-        builtin::init(&mut heap).expect("failed to initialize builtin objects");
+        let mut heap = Heap {
+            objects,
+            object_ids: (0..Self::USERSTART as u64).collect(),
+            next_object_id: Self::USERSTART as u64,
+            loc: None,
+            debugger_hook: None,
+            parser_hook: None,
+            stdout: None,
+            stderr: None,
+            trace: false,
+            microtasks: Vec::new(),
+            limits: globals.limits.clone(),
+            steps: 0,
+            joining: Vec::new(),
+            console: ConsoleState::default(),
+            process: globals.process,
+        };
+        builtin::init(&mut heap, globals).expect("failed to initialize builtin objects");
+        let global_proto = match globals.global_proto.clone() {
+            Some(mut proto) => {
+                if !globals.object_proto_in_globals {
+                    proto.proto = Heap::NULL;
+                }
+                heap.alloc(proto)
+            }
+            None if globals.object_proto_in_globals => Heap::OBJECT_PROTO,
+            None => Heap::NULL,
+        };
+        heap.get_mut(Heap::GLOBAL).proto = global_proto;
         heap
     }
 
+    /// Makes `debugger;` statements call `hook` with the heap in its current scope.
+    /// Without a hook, `debugger;` does nothing.
+    pub fn set_debugger_hook(&mut self, hook: DebuggerHook) {
+        self.debugger_hook = Some(hook);
+    }
+
+    /// Lets builtins like `Function(...)` parse code, [`crate::runtime::Runtime`] sets it up.
+    /// Without a hook, they throw a TypeError.
+    pub fn set_parser_hook(&mut self, hook: Option<ParserHook>) {
+        self.parser_hook = hook;
+    }
+
+    pub(crate) fn parse(&mut self, input: &str) -> JSResult<Program> {
+        let hook = (self.parser_hook.clone())
+            .ok_or_else(|| Exception::type_error(TypeError::NO_PARSER, input))?;
+        hook(input, self)
+    }
+
+    /// Redirects `console.log()` and alike. Without a sink, lines go to the process stdout
+    /// (or nowhere without `std`).
+    pub fn set_stdout(&mut self, sink: OutputSink) {
+        self.stdout = Some(sink);
+    }
+
+    /// Redirects `console.error()`, `console.warn()` and tracing, see [`Heap::set_stdout`].
+    pub fn set_stderr(&mut self, sink: OutputSink) {
+        self.stderr = Some(sink);
+    }
+
+    pub(crate) fn print(&mut self, line: &str) {
+        match self.stdout.as_mut() {
+            Some(sink) => sink(line),
+            #[cfg(feature = "std")]
+            None => println!("{}", line),
+            #[cfg(not(feature = "std"))]
+            None => (),
+        }
+    }
+
+    pub(crate) fn eprint(&mut self, line: &str) {
+        match self.stderr.as_mut() {
+            Some(sink) => sink(line),
+            #[cfg(feature = "std")]
+            None => eprintln!("{}", line),
+            #[cfg(not(feature = "std"))]
+            None => (),
+        }
+    }
+
+    /// Logs every statement with its location to stderr before interpreting it.
+    pub fn set_trace(&mut self, trace: bool) {
+        self.trace = trace;
+    }
+
+    pub(crate) fn is_tracing(&self) -> bool {
+        self.trace
+    }
+
+    /// Schedules a callback for [`Heap::run_microtasks`], see `queueMicrotask()`.
+    pub(crate) fn enqueue_microtask(&mut self, func_ref: JSRef) {
+        self.microtasks.push(func_ref);
+    }
+
+    /// Calls the queued microtasks in order, including those queued meanwhile.
+    /// Stops at the first exception, the rest stays in the queue.
+    pub fn run_microtasks(&mut self) -> JSResult<()> {
+        while !self.microtasks.is_empty() {
+            let func_ref = self.microtasks.remove(0);
+            let call = CallContext::from(vec![]).with_this(Heap::GLOBAL);
+            self.execute(func_ref, call)?;
+        }
+        Ok(())
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// How many statements were interpreted since the heap was made or [`Heap::reset_steps`].
+    /// Going over [`Limits::max_steps`] interrupts the script: `catch` does not see the
+    /// [`Exception::Interrupted`], but `finally` blocks run, each with `max_steps` of its own.
+    /// ```
+    /// # use sljs::{ast::stmt, Exception, HeapBuilder, Limits, Program};
+    /// let limits = Limits { max_steps: Some(1000), ..Limits::default() };
+    /// let mut heap = HeapBuilder::new().limits(limits).build();
+    /// let forever = Program::from_stmts(vec![stmt::while_(true, stmt::block(vec![]).into())]);
+    /// assert!(matches!(heap.evaluate(&forever), Err(Exception::Interrupted(_))));
+    /// assert!(heap.steps() > 1000);
+    /// ```
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Counts [`Heap::steps`] from zero again, e.g. before evaluating the next script.
+    pub fn reset_steps(&mut self) {
+        self.steps = 0;
+    }
+
+    /// Counts a statement, interrupts the script when it goes over [`Limits::max_steps`].
+    pub(crate) fn count_step(&mut self) -> JSResult<()> {
+        self.steps += 1;
+        match self.limits.max_steps {
+            Some(max_steps) if self.steps > max_steps => Err(Exception::Interrupted(self.steps)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Runs `f` with [`Heap::steps`] counted from zero, then adds them up:
+    /// a `finally` block cleans up after an interrupt with a budget of its own.
+    pub(crate) fn with_fresh_steps<T>(&mut self, f: impl FnOnce(&mut Heap) -> T) -> T {
+        let steps = core::mem::replace(&mut self.steps, 0);
+        let result = f(self);
+        self.steps += steps;
+        result
+    }
+
+    /// Aborts an `AbortSignal` from the host, e.g. from a hook or a host function,
+    /// for scripts that check `signal.aborted` or call `signal.throwIfAborted()`.
+    /// An `undefined` reason becomes an `AbortError`.
+    pub fn abort_signal(&mut self, signal_ref: JSRef, reason: JSValue) -> JSResult<()> {
+        builtin::abort::abort(signal_ref, reason, self)
+    }
+
+    /// Captures the object graph to [`Heap::restore`] it later, e.g. to run
+    /// many scripts in a fresh copy of an expensive setup.
+    /// Hooks, output sinks and limits are configuration and are not captured.
+    /// ```
+    /// # use sljs::{Heap, JSValue};
+    /// let mut heap = Heap::new();
+    /// let snapshot = heap.snapshot();
+    /// heap.set_global("answer", JSValue::from(42)).unwrap();
+    /// heap.restore(&snapshot);
+    /// assert_eq!(heap.get_global("answer"), None);
+    /// ```
+    pub fn snapshot(&self) -> HeapSnapshot {
+        HeapSnapshot {
+            objects: self.objects.clone(),
+            object_ids: self.object_ids.clone(),
+            next_object_id: self.next_object_id,
+            microtasks: self.microtasks.clone(),
+            console: self.console.clone(),
+        }
+    }
+
+    /// Resets all objects and globals to the state of a [`Heap::snapshot`].
+    pub fn restore(&mut self, snapshot: &HeapSnapshot) {
+        self.objects = snapshot.objects.clone();
+        self.object_ids = snapshot.object_ids.clone();
+        self.next_object_id = snapshot.next_object_id;
+        self.microtasks = snapshot.microtasks.clone();
+        self.console = snapshot.console.clone();
+        self.loc = None;
+    }
+
+    /// Throws a RangeError if a string of `length` characters is over [`Limits`].
+    pub(crate) fn check_string_length(&self, length: usize) -> JSResult<()> {
+        match length <= self.limits.max_string_length {
+            true => Ok(()),
+            false => Err(Exception::range_error(
+                RangeError::INVALID_STRING_LENGTH,
+                length as f64,
+            )),
+        }
+    }
+
+    /// Throws a RangeError if an array of `length` elements is over [`Limits`].
+    pub(crate) fn check_array_length(&self, length: usize) -> JSResult<()> {
+        match length <= self.limits.max_array_length {
+            true => Ok(()),
+            false => Err(Exception::range_error(
+                RangeError::INVALID_LENGTH,
+                length as f64,
+            )),
+        }
+    }
+
+    pub(crate) fn run_debugger_hook(&mut self) {
+        // the hook is taken out for the duration of the call, so it could borrow the heap
+        if let Some(mut hook) = self.debugger_hook.take() {
+            hook(self);
+            self.debugger_hook = Some(hook);
+        }
+    }
+
     pub fn get(&self, objref: JSRef) -> &JSObject {
         self.objects
             .get(objref.0)
@@ -109,9 +538,24 @@ impl Heap {
     pub fn alloc(&mut self, object: JSObject) -> JSRef {
         let ind = self.objects.len();
         self.objects.push(object);
+        self.object_ids.push(self.next_object_id);
+        self.next_object_id += 1;
         JSRef(ind)
     }
 
+    /// A number that identifies the object for debugging, e.g. in logs or leak hunting.
+    /// Ids count allocations: unlike heap slots, they are never reused.
+    /// ```
+    /// # use sljs::{Heap, JSObject};
+    /// let mut heap = Heap::new();
+    /// let a = heap.alloc(JSObject::new());
+    /// let b = heap.alloc(JSObject::new());
+    /// assert!(heap.object_id(a) < heap.object_id(b));
+    /// ```
+    pub fn object_id(&self, objref: JSRef) -> u64 {
+        *(self.object_ids.get(objref.0)).unwrap_or_else(|| panic!("{:?} is invalid", objref))
+    }
+
     pub fn alloc_func(&mut self, func: HostFn) -> JSRef {
         let func_obj = JSObject::from_func(func);
         self.alloc(func_obj)
@@ -182,6 +626,155 @@ impl Heap {
         }
     }
 
+    /// Make a human-readable representation of `value` in the style of `console.log`:
+    /// ```
+    /// # use serde_json::json;
+    /// # use sljs::{Heap, JSValue};
+    /// # let mut heap = Heap::new();
+    /// let object = heap.object_from_json(&json!({"a": [1, "two"]}));
+    /// assert_eq!(heap.inspect(&object), "{ a: [ 1, 'two' ] }");
+    ///
+    /// // strings are quoted only inside containers:
+    /// assert_eq!(heap.inspect(&JSValue::from("two")), "two");
+    /// ```
+    /// Cyclic references are displayed as `[Circular]`.
+    pub fn inspect(&self, value: &JSValue) -> String {
+        match value {
+            JSValue::String(s) => s.to_string(),
+            _ => self.inspect_nested(value, &mut Vec::new()),
+        }
+    }
+
+    /// Like [`Heap::inspect`], but strings are quoted as inside containers,
+    /// e.g. for `console.table()` cells.
+    pub(crate) fn inspect_quoted(&self, value: &JSValue) -> String {
+        self.inspect_nested(value, &mut Vec::new())
+    }
+
+    /// Renders an uncaught exception for the user: `Error` objects (and internal errors)
+    /// as `Name: message` followed by the call stack, other thrown values as
+    /// [`Heap::inspect`] shows them.
+    /// ```
+    /// # use sljs::{Exception, Heap, JSValue};
+    /// # let mut heap = Heap::new();
+    /// let exc = Exception::UserThrown(JSValue::from("oops"));
+    /// assert_eq!(heap.format_exception(&exc), "Uncaught oops");
+    /// ```
+    pub fn format_exception(&mut self, exc: &Exception) -> String {
+        let message = match exc {
+            Exception::UserThrown(JSValue::Ref(r)) if r.has_proto(Heap::ERROR_PROTO, self) => {
+                let error = JSValue::from(*r);
+                match error.stringify(self) {
+                    Ok(message) => message.to_string(),
+                    Err(_) => self.inspect(&error),
+                }
+            }
+            Exception::UserThrown(value) => return format!("Uncaught {}", self.inspect(value)),
+            Exception::Interrupted(steps) => return format!("Interrupted after {} steps", steps),
+            Exception::Jump(_) => return format!("{:?}", exc),
+            _ => format!("{}: {}", exc.error_class(), exc.message(self)),
+        };
+        let stack = source::callstack(self).unwrap_or_default();
+        let text = format!("Uncaught {}\n{}", message, stack);
+        text.trim_end().to_string()
+    }
+
+    /// Like `String(n)`, but `-0` is shown.
+    fn inspect_number(n: JSNumber) -> String {
+        match n == 0.0 && n.is_sign_negative() {
+            true => "-0".into(),
+            false => builtin::number::to_decimal_string(n),
+        }
+    }
+
+    fn inspect_holes(count: usize) -> String {
+        match count {
+            1 => "<1 empty item>".into(),
+            _ => format!("<{} empty items>", count),
+        }
+    }
+
+    /// `ancestors` are the objects being displayed at the moment, used to detect cycles.
+    fn inspect_nested(&self, value: &JSValue, ancestors: &mut Vec<JSRef>) -> String {
+        let objref = match value {
+            JSValue::Undefined => return "undefined".into(),
+            JSValue::Bool(b) => return b.to_string(),
+            JSValue::Number(n) => return Self::inspect_number(*n),
+            JSValue::BigInt(n) => return format!("{}n", n),
+            JSValue::String(s) => return format!("'{}'", s.replace('\'', "\\'")),
+            JSValue::Ref(Heap::NULL) => return "null".into(),
+            JSValue::Ref(r) => *r,
+        };
+        if ancestors.contains(&objref) {
+            return "[Circular]".into();
+        }
+        ancestors.push(objref);
+
+        let object = self.get(objref);
+        let prefix = match &object.value {
+            ObjectValue::Closure(closure) => match closure.function.id.as_ref() {
+                Some(id) => format!("[Function: {}]", id.as_str()),
+                None => "[Function (anonymous)]".into(),
+            },
+            ObjectValue::HostFn(_) => "[Function (native)]".into(),
+            ObjectValue::Boolean(b) => format!("[Boolean: {}]", b),
+            ObjectValue::Number(n) => format!("[Number: {}]", Self::inspect_number(*n)),
+            ObjectValue::String(s) => format!(
+                "[String: {}]",
+                self.inspect_nested(&JSValue::from(s.clone()), ancestors)
+            ),
+            ObjectValue::Array(_) | ObjectValue::None => String::new(),
+        };
+
+        let mut items = Vec::new();
+        if let Some(array) = object.as_array() {
+            let mut holes = 0;
+            for item in array.storage.iter() {
+                match item {
+                    None => holes += 1,
+                    Some(item) => {
+                        if holes > 0 {
+                            items.push(Self::inspect_holes(holes));
+                            holes = 0;
+                        }
+                        items.push(self.inspect_nested(item, ancestors));
+                    }
+                }
+            }
+            if holes > 0 {
+                items.push(Self::inspect_holes(holes));
+            }
+        }
+        // TODO: functions have an enumerable `length`, skip their properties for now
+        let properties = (object.ordered_properties()).filter(|_| !object.is_callable());
+        for (key, property) in properties {
+            if !property.access.enumerable() {
+                continue;
+            }
+            let key = match is_valid_identifier(key) {
+                true => key.to_string(),
+                false => self.inspect_nested(&JSValue::from(key.clone()), ancestors),
+            };
+            let value = match &property.content {
+                Content::Value(value) => self.inspect_nested(value, ancestors),
+                accessor => accessor.describe_accessor().to_string(),
+            };
+            items.push(format!("{}: {}", key, value));
+        }
+        ancestors.pop();
+
+        let (open, close) = match object.as_array() {
+            Some(_) => ("[", "]"),
+            None => ("{", "}"),
+        };
+        match (prefix.is_empty(), items.is_empty()) {
+            (true, true) => format!("{}{}", open, close),
+            (true, false) => format!("{} {} {}", open, items.join(", "), close),
+            (false, true) => prefix,
+            (false, false) => format!("{} {} {} {}", prefix, open, items.join(", "), close),
+        }
+    }
+
     /// Find out what `this` currently is.
     pub fn interpret_this(&mut self) -> JSRef {
         self.lookup_var(Self::SCOPE_THIS)
@@ -190,6 +783,14 @@ impl Heap {
             .expect("this must be JSValue::Ref")
     }
 
+    /// Find out what `new.target` currently is.
+    pub fn interpret_new_target(&mut self) -> JSValue {
+        match self.lookup_var(Self::NEW_TARGET) {
+            Some(place) => place.to_value(self).unwrap_or(JSValue::Undefined),
+            None => JSValue::Undefined,
+        }
+    }
+
     pub(crate) fn is_scope(&self, objref: JSRef) -> bool {
         objref == Self::GLOBAL || self.get(objref).get_own_value(Heap::SAVED_SCOPE).is_some()
     }
@@ -239,23 +840,37 @@ impl Heap {
         Ok(())
     }
 
+    /// Looks up `name` in one scope object.
+    /// A `with` scope also looks it up in the `with` object and its prototypes.
+    fn lookup_in_scope(&self, scope_ref: JSRef, name: &str) -> Option<Interpreted> {
+        let scope = self.get(scope_ref);
+        if scope.get_own_value(name).is_some() {
+            return Some(Interpreted::member(scope_ref, name));
+        }
+        match scope.get_own_value(Self::WITH_OBJECT) {
+            Some(JSValue::Ref(object_ref)) => (self.lookup_protochain(object_ref, name))
+                .map(|_| Interpreted::member(object_ref, name)),
+            _ => None,
+        }
+    }
+
     pub fn lookup_var(&self, name: &str) -> Option<Interpreted> {
         if let Some(local_ref) = self.local_scope() {
-            let local = self.get(local_ref);
-            if local.get_own_value(name).is_some() {
-                return Some(Interpreted::member(local_ref, name));
+            if let Some(place) = self.lookup_in_scope(local_ref, name) {
+                return Some(place);
             }
 
             // captured scopes lookup
+            let local = self.get(local_ref);
             let mut scope_ref = match local.get_own_value(Self::CAPTURED_SCOPE) {
                 Some(JSValue::Ref(scope_ref)) => scope_ref,
                 _ => Heap::NULL,
             };
             while scope_ref != Heap::NULL {
-                let scope = self.get(scope_ref);
-                if scope.get_own_value(name).is_some() {
-                    return Some(Interpreted::member(scope_ref, name));
+                if let Some(place) = self.lookup_in_scope(scope_ref, name) {
+                    return Some(place);
                 }
+                let scope = self.get(scope_ref);
 
                 scope_ref = match scope.get_own_value(Self::CAPTURED_SCOPE) {
                     Some(JSValue::Ref(scope_ref)) => scope_ref,
@@ -290,6 +905,44 @@ impl Heap {
         Ok(Interpreted::from(scoperef))
     }
 
+    /// Defines or overwrites a global variable for scripts to see.
+    /// It's a TypeError to overwrite a readonly one, e.g. `undefined`.
+    pub fn set_global(&mut self, name: &str, value: JSValue) -> JSResult<()> {
+        self.get_mut(Heap::GLOBAL).set_property(name, value)
+    }
+
+    /// Reads a global variable, e.g. one defined by a script with `var`.
+    pub fn get_global(&self, name: &str) -> Option<JSValue> {
+        self.get(Heap::GLOBAL).get_own_value(name)
+    }
+
+    /// Defines (or redefines) an enumerable and configurable accessor property `name`
+    /// on `objref` with native functions as its getter and/or setter.
+    /// The getter is called with the object as `this`, the setter gets the value as its argument.
+    /// ```
+    /// # use sljs::{CallContext, Heap, Interpreted, JSObject, JSResult, JSValue};
+    /// fn now(_call: CallContext, _heap: &mut Heap) -> JSResult<Interpreted> {
+    ///     Ok(Interpreted::from(1234.0))
+    /// }
+    ///
+    /// let mut heap = Heap::new();
+    /// let clock = heap.alloc(JSObject::new());
+    /// heap.define_accessor(clock, "now", Some(now), None).unwrap();
+    /// heap.set_global("clock", JSValue::from(clock)).unwrap();
+    /// ```
+    pub fn define_accessor(
+        &mut self,
+        objref: JSRef,
+        name: &str,
+        get: Option<HostFn>,
+        set: Option<HostFn>,
+    ) -> JSResult<()> {
+        let get = get.map(|func| self.alloc_func(func));
+        let set = set.map(|func| self.alloc_func(func));
+        let access = Access::CONF | Access::ENUM;
+        self.get_mut(objref).set_accessors(name, get, set, access)
+    }
+
     pub fn enter_new_scope<T, F>(
         &mut self,
         this_ref: JSRef,
@@ -309,6 +962,74 @@ impl Heap {
         result
     }
 
+    /// Runs `action` in the global scope, hiding the current local scope chain,
+    /// e.g. for an indirect `eval()`.
+    pub fn in_global_scope<T, F>(&mut self, action: F) -> JSResult<T>
+    where
+        F: FnOnce(&mut Heap) -> JSResult<T>,
+    {
+        let saved_scope_ref = match self.local_scope() {
+            None => return action(self),
+            Some(scope_ref) => scope_ref,
+        };
+        self.set_local_scope(Heap::GLOBAL)?;
+        let result = action(self);
+        self.set_local_scope(saved_scope_ref)?;
+        result
+    }
+
+    /// The current local scope, or [`Heap::GLOBAL`] at the top level.
+    /// It can be kept (e.g. by a debugger hook) for [`Heap::interpret_in_scope`] later.
+    pub fn current_scope(&self) -> JSRef {
+        self.local_scope().unwrap_or(Heap::GLOBAL)
+    }
+
+    /// Evaluates `interpretable` with `scope_ref` (see [`Heap::current_scope`]) as
+    /// the local scope, e.g. to inspect a paused or already returned call frame.
+    /// Its locals, `this` and captured variables are visible, new `var`s go into it.
+    ///
+    /// ```
+    /// # use sljs::{Heap, JSValue, ast::expr};
+    /// let mut heap = Heap::new();
+    /// heap.set_global("x", JSValue::from(1)).unwrap();
+    /// let scope = heap.current_scope();
+    /// let result = heap.interpret_in_scope(scope, &expr::id("x"));
+    /// assert_eq!(result, Ok(JSValue::from(1)));
+    /// ```
+    pub fn interpret_in_scope<T: Interpretable>(
+        &mut self,
+        scope_ref: JSRef,
+        interpretable: &T,
+    ) -> JSResult<JSValue> {
+        if !self.is_scope(scope_ref) {
+            return Err(Exception::type_error(TypeError::NOT_A_SCOPE, scope_ref));
+        }
+        let saved_scope_ref = self.current_scope();
+        self.set_local_scope(scope_ref)?;
+        let result = self.evaluate(interpretable);
+        self.set_local_scope(saved_scope_ref)?;
+        result
+    }
+
+    /// The local scope is a system property of the global object: it's not enumerable
+    /// and is removed with `shift_remove` to keep the order of global properties.
+    fn set_local_scope(&mut self, scope_ref: JSRef) -> JSResult<()> {
+        let global = self.get_mut(Heap::GLOBAL);
+        if scope_ref == Heap::GLOBAL {
+            global.properties.shift_remove(Self::LOCAL_SCOPE);
+        } else {
+            let content = Content::from(scope_ref);
+            let property = Property {
+                content,
+                access: Access::empty(),
+            };
+            global
+                .properties
+                .insert(JSString::from(Self::LOCAL_SCOPE), property);
+        }
+        Ok(())
+    }
+
     fn push_scope(&mut self, this_ref: JSRef) -> JSResult<JSRef> {
         let old_scope_ref = self.local_scope().unwrap_or(Heap::GLOBAL);
 
@@ -318,8 +1039,7 @@ impl Heap {
         scope_object.set_system(Self::SCOPE_THIS, this_ref)?;
 
         let new_scope_ref = self.alloc(scope_object);
-        self.get_mut(Heap::GLOBAL)
-            .set_even_nonwritable(Self::LOCAL_SCOPE, new_scope_ref)?;
+        self.set_local_scope(new_scope_ref)?;
         Ok(new_scope_ref)
     }
 
@@ -331,14 +1051,7 @@ impl Heap {
             .and_then(|prop| prop.to_ref())
             .expect("saved scope is not a reference"); // yes, panic, this interpreter is broken.
 
-        let global = self.get_mut(Heap::GLOBAL);
-        if saved_scope_ref == Heap::GLOBAL {
-            global.properties.remove(Self::LOCAL_SCOPE);
-        } else {
-            global.set_even_nonwritable(Self::LOCAL_SCOPE, saved_scope_ref)?;
-        }
-
-        Ok(())
+        self.set_local_scope(saved_scope_ref)
     }
 
     /// Find the location of `propname` on the prototype chain of `objref`.
@@ -360,12 +1073,49 @@ impl Heap {
         interpretable.interpret(self)?.to_value(self)
     }
 
+    /// Like [`Heap::evaluate`], but also returns what was written to stdout meanwhile
+    /// (e.g. by `console.log()`), one `\n`-terminated line per write.
+    /// The output is captured instead of going to the sink set by [`Heap::set_stdout`],
+    /// and is returned even if evaluation throws.
+    ///
+    /// ```
+    /// # use sljs::{Heap, JSValue, ast::expr};
+    /// let mut heap = Heap::new();
+    /// let log = expr::memb(expr::id("console"), "log");
+    /// let program = expr::call(log, vec![expr::lit("hi")]);
+    /// let (result, output) = heap.evaluate_captured(&program);
+    /// assert_eq!(result, Ok(JSValue::Undefined));
+    /// assert_eq!(output, "hi\n");
+    /// ```
+    pub fn evaluate_captured<T: Interpretable>(
+        &mut self,
+        interpretable: &T,
+    ) -> (JSResult<JSValue>, String) {
+        let captured = Rc::new(RefCell::new(String::new()));
+        let output = Rc::clone(&captured);
+        let sink: OutputSink = Box::new(move |line| {
+            let mut output = output.borrow_mut();
+            output.push_str(line);
+            output.push('\n');
+        });
+        let saved_stdout = self.stdout.replace(sink);
+
+        let result = self.evaluate(interpretable);
+
+        self.stdout = saved_stdout;
+        let output = captured.borrow().clone();
+        (result, output)
+    }
+
     /// Given a `func_ref` to a closure or a native call and a set of arguments,
     /// executes the function. `this_ref` is bound as `this`.
     pub fn execute(&mut self, func_ref: JSRef, mut call: CallContext) -> JSResult<Interpreted> {
         if call.loc.as_ref().is_none() {
             call.loc = self.loc.clone();
         }
+        if let Some(target_ref) = builtin::function::unbind(func_ref, &mut call, self) {
+            return self.execute(target_ref, call);
+        }
         // Yes, we do need a clone() to workaround borrow checker:
         match &self.get(func_ref).value {
             ObjectValue::HostFn(vmcall) => vmcall.clone().call(call, self),
@@ -5,8 +5,10 @@ use crate::Jump;
 use crate::ast::*; // yes, EVERYTHING
 use crate::builtin;
 use crate::{
-    function::Closure, object::Access, CallContext, Exception, Heap, Interpreted, JSObject,
-    JSResult, JSValue,
+    function::{Closure, TailCall},
+    object::{array_index, Access},
+    value, CallContext, Exception, Heap, Interpreted, JSBigInt, JSObject, JSRef, JSResult, JSValue,
+    PrimitiveHint,
 };
 
 // ==============================================
@@ -34,24 +36,48 @@ impl Interpretable for Program {
 
 impl Interpretable for Statement {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
+        let completion = self.interpret_completion(heap)?;
+        Ok(completion.unwrap_or(Interpreted::VOID))
+    }
+}
+
+impl Statement {
+    /// Runs the statement and returns its completion value (e.g. for `eval`),
+    /// `None` for statements that do not have one: `;`, declarations, `{}`.
+    fn interpret_completion(&self, heap: &mut Heap) -> JSResult<Option<Interpreted>> {
         heap.loc = self.loc.clone();
-        match &self.stmt {
-            Stmt::Empty => Ok(Interpreted::VOID),
+        heap.count_step()?;
+        if heap.is_tracing() {
+            let line = match &self.loc {
+                Some(loc) => format!("[trace] {} {}", loc, self.stmt.kind()),
+                None => format!("[trace] ? {}", self.stmt.kind()),
+            };
+            heap.eprint(&line);
+        }
+        let result = match &self.stmt {
+            Stmt::Empty => return Ok(None),
             Stmt::Expr(stmt) => stmt.interpret(heap),
-            Stmt::Block(stmt) => stmt.interpret(heap),
+            Stmt::Block(stmt) => return stmt.interpret_completion(heap),
             Stmt::If(stmt) => stmt.interpret(heap),
             Stmt::Switch(stmt) => stmt.interpret(heap),
             Stmt::For(stmt) => stmt.interpret(heap),
             Stmt::ForIn(stmt) => stmt.interpret(heap),
+            Stmt::ForOf(stmt) => stmt.interpret(heap),
             Stmt::Break(stmt) => stmt.interpret(heap),
             Stmt::Continue(stmt) => stmt.interpret(heap),
             Stmt::Label(stmt) => stmt.interpret(heap),
             Stmt::Return(stmt) => stmt.interpret(heap),
             Stmt::Throw(stmt) => stmt.interpret(heap),
             Stmt::Try(stmt) => stmt.interpret(heap),
-            Stmt::Variable(stmt) => stmt.interpret(heap),
-            Stmt::Function(stmt) => stmt.interpret(heap),
-        }
+            Stmt::With(stmt) => stmt.interpret(heap),
+            Stmt::Debugger => {
+                heap.run_debugger_hook();
+                return Ok(None);
+            }
+            Stmt::Variable(stmt) => return stmt.interpret(heap).map(|_| None),
+            Stmt::Function(stmt) => return stmt.interpret(heap).map(|_| None),
+        };
+        result.map(Some)
     }
 }
 
@@ -59,20 +85,49 @@ impl Interpretable for Statement {
 
 impl Interpretable for BlockStatement {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
+        let completion = self.interpret_completion(heap)?;
+        Ok(completion.unwrap_or(Interpreted::VOID))
+    }
+}
+
+impl BlockStatement {
+    /// The completion value of a block is the one of its last statement that has any.
+    fn interpret_completion(&self, heap: &mut Heap) -> JSResult<Option<Interpreted>> {
         let this_ref = heap.interpret_this();
         let outer_scope = heap.local_scope().unwrap_or(Heap::GLOBAL);
         heap.enter_new_scope(this_ref, outer_scope, |heap| {
             heap.declare(self.bindings.iter(), [].into_iter())?;
 
-            let mut result = Interpreted::VOID;
+            let mut result = None;
             for stmt in self.body.iter() {
-                result = stmt.interpret(heap)?;
+                if let Some(value) = stmt.interpret_completion(heap)? {
+                    result = Some(value);
+                }
             }
             Ok(result)
         })
     }
 }
 
+impl Interpretable for WithStatement {
+    fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
+        let value = self.object.evaluate(heap)?;
+        let object_ref = match value {
+            JSValue::Undefined | JSValue::Ref(Heap::NULL) => {
+                return Err(Exception::type_error(TypeError::NOT_AN_OBJECT, value))
+            }
+            value => value.objectify(heap),
+        };
+
+        let this_ref = heap.interpret_this();
+        let outer_scope = heap.local_scope().unwrap_or(Heap::GLOBAL);
+        heap.enter_new_scope(this_ref, outer_scope, |heap| {
+            heap.scope_mut().set_system(Heap::WITH_OBJECT, object_ref)?;
+            self.body.interpret(heap)
+        })
+    }
+}
+
 impl Interpretable for IfStatement {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
         let cond = self.test.evaluate(heap)?;
@@ -109,6 +164,8 @@ impl Interpretable for SwitchStatement {
             }
         }
 
+        // `default` is only used when no case matches, wherever it is;
+        // then it falls through to the cases after it as usual.
         let end = self.cases.len();
         let restart_index = found_case.or(default).unwrap_or(end);
 
@@ -129,15 +186,33 @@ impl Interpretable for SwitchStatement {
 }
 
 impl ForStatement {
+    /// Runs the loop; `continue label` is handled here for a loop with `label`,
+    /// so the update runs once per iteration either way.
+    fn run(&self, heap: &mut Heap, label: Option<&Identifier>) -> JSResult<()> {
+        if let Some(names) = self.head_bindings() {
+            return self.do_loop_per_iteration(heap, &names, label);
+        }
+        // `init` is the first iteration of a `do..while` loop: it may `continue` or `break`
+        match self.init.interpret(heap) {
+            Ok(_) => (),
+            Err(Exception::Jump(Jump::Continue(None))) => (),
+            Err(Exception::Jump(Jump::Continue(Some(target)))) if Some(&target) == label => {}
+            Err(Exception::Jump(Jump::Break(None))) => return Ok(()),
+            Err(e) => return Err(e),
+        }
+        self.do_loop(heap, label)
+    }
+
     /// `do_loop()` executes the loop except its `init` statement.
     /// `init` must be interpreted before this, if needed.
-    fn do_loop(&self, heap: &mut Heap) -> Result<(), Exception> {
+    fn do_loop(&self, heap: &mut Heap, label: Option<&Identifier>) -> Result<(), Exception> {
         while self.should_iterate(heap)? {
             // body
             let result = self.body.interpret(heap);
             match result {
                 Ok(_) => (),
                 Err(Exception::Jump(Jump::Continue(None))) => (),
+                Err(Exception::Jump(Jump::Continue(Some(target)))) if Some(&target) == label => {}
                 Err(Exception::Jump(Jump::Break(None))) => break,
                 Err(e) => return Err(e),
             };
@@ -165,47 +240,136 @@ impl ForStatement {
     }
 }
 
+impl ForStatement {
+    /// Names declared by `let` in the loop head, if any.
+    fn head_bindings(&self) -> Option<Vec<Identifier>> {
+        match &self.init.stmt {
+            Stmt::Variable(var) if var.kind != DeclarationKind::Var => {
+                let names = (var.declarations.iter()).flat_map(|decl| decl.name.bound_names());
+                Some(names.cloned().collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads the current values of `names` from the local scope.
+    fn save_bindings(heap: &Heap, names: &[Identifier]) -> Vec<JSValue> {
+        let scope_ref = heap.local_scope().expect("no loop scope");
+        let scope = heap.get(scope_ref);
+        let value_of = |name: &Identifier| scope.get_own_value(name.as_str());
+        names
+            .iter()
+            .map(|name| value_of(name).unwrap_or(JSValue::Undefined))
+            .collect()
+    }
+
+    /// Runs a `let`-headed loop: each iteration gets a fresh scope with
+    /// a copy of the head bindings, so closures capture per-iteration values.
+    /// `continue label` is handled here for a loop with `label`.
+    fn do_loop_per_iteration(
+        &self,
+        heap: &mut Heap,
+        names: &[Identifier],
+        label: Option<&Identifier>,
+    ) -> JSResult<()> {
+        let this_ref = heap.interpret_this();
+        let outer_scope = heap.local_scope().unwrap_or(Heap::GLOBAL);
+
+        let mut values = heap.enter_new_scope(this_ref, outer_scope, |heap| {
+            heap.declare(names.iter(), [].into_iter())?;
+            self.init.interpret(heap)?;
+            Ok(Self::save_bindings(heap, names))
+        })?;
+
+        let mut first = true;
+        loop {
+            let next = heap.enter_new_scope(this_ref, outer_scope, |heap| {
+                heap.declare(names.iter(), [].into_iter())?;
+                for (name, value) in names.iter().zip(values.iter()) {
+                    heap.scope_mut()
+                        .set_property(name.as_str(), value.clone())?;
+                }
+                if let Stmt::Variable(var) = &self.init.stmt {
+                    var.make_const(heap);
+                }
+
+                if !first {
+                    self.do_update(heap)?;
+                }
+                if !self.should_iterate(heap)? {
+                    return Ok(None);
+                }
+
+                match self.body.interpret(heap) {
+                    Ok(_) => (),
+                    Err(Exception::Jump(Jump::Continue(None))) => (),
+                    Err(Exception::Jump(Jump::Continue(Some(target))))
+                        if Some(&target) == label => {}
+                    Err(Exception::Jump(Jump::Break(None))) => return Ok(None),
+                    Err(e) => return Err(e),
+                };
+                Ok(Some(Self::save_bindings(heap, names)))
+            })?;
+
+            match next {
+                Some(next) => values = next,
+                None => return Ok(()),
+            }
+            first = false;
+        }
+    }
+}
+
 impl Interpretable for ForStatement {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
-        self.init.interpret(heap)?;
-        self.do_loop(heap)?;
+        self.run(heap, None)?;
         Ok(Interpreted::VOID)
     }
 }
 
-impl ForInStatement {}
-
-impl Interpretable for ForInStatement {
-    fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
-        let iteratee = self.right.evaluate(heap)?.objectify(heap);
-
-        let assignexpr = match &self.left {
-            ForInTarget::Expr(expr) => expr.clone(),
+impl ForInTarget {
+    /// Assigns the next value of a `for-in`/`for-of` loop.
+    fn assign(&self, value: JSValue, heap: &mut Heap) -> JSResult<()> {
+        match self {
+            ForInTarget::Expr(expr) => (expr.interpret(heap)?)
+                .put_value(value, heap)
+                .or_else(crate::error::ignore_set_readonly),
             ForInTarget::Var(vardecl) => {
                 debug_assert_eq!(vardecl.declarations.len(), 1);
-                let ident = &vardecl.declarations[0].name;
-                let idexpr = Expr::Identifier(Identifier::from(ident.as_str()));
-                Expression {
-                    expr: idexpr,
-                    loc: None,
-                }
+                vardecl.declarations[0].name.bind(value, heap)?;
+                vardecl.make_const(heap);
+                Ok(())
             }
-        };
+        }
+    }
+
+    /// `let`/`const` names that need a fresh binding for each iteration.
+    fn iteration_bindings(&self) -> Vec<&Identifier> {
+        match self {
+            ForInTarget::Var(vardecl) if vardecl.kind != DeclarationKind::Var => {
+                (vardecl.declarations.iter())
+                    .flat_map(|decl| decl.name.bound_names())
+                    .collect()
+            }
+            _ => vec![],
+        }
+    }
+}
+
+impl ForInStatement {
+    /// Runs the loop; `continue label` is handled here for a loop with `label`.
+    fn do_loop(&self, heap: &mut Heap, label: Option<&Identifier>) -> JSResult<()> {
+        let iteratee = self.right.evaluate(heap)?.objectify(heap);
 
         let mut visited = HashSet::new();
         let mut objref = iteratee;
         while objref != Heap::NULL {
             let object = heap.get(objref);
-            let mut keys = (object.properties.keys())
-                .cloned()
-                .collect::<HashSet<JSString>>();
-            if let Some(array) = object.as_array() {
-                let indices = 0..array.storage.len();
-                keys.extend(indices.map(|i| i.to_string().into()));
-            }
-            // TODO: strings iteration
+            let mut keys = object.element_keys();
+            // non-enumerable properties are visited too: they shadow inherited ones
+            keys.extend(object.ordered_properties().map(|(key, _)| key.clone()));
 
-            for propname in keys.drain() {
+            for propname in keys {
                 if visited.contains(&propname) {
                     continue;
                 }
@@ -214,34 +378,39 @@ impl Interpretable for ForInStatement {
                 let object = heap.get(objref);
                 match object.properties.get(&propname) {
                     Some(p) if p.access.enumerable() => (),
-                    None if object.as_array().is_some() && propname.parse::<usize>().is_ok() => (),
+                    None if object.get_own_content(&propname).is_some() => (), // an element
                     Some(_) => continue, // not enumerable, skip
                     None => continue,    // the property has disappeared!
                 };
 
-                let propname = match propname.parse::<usize>() {
-                    Ok(p) => JSValue::from(p as f64),
-                    _ => JSValue::from(propname.as_str()),
+                let propname = match array_index(&propname) {
+                    Some(index) => JSValue::from(index as f64),
+                    None => JSValue::from(propname.as_str()),
                 };
-                assignexpr
-                    .interpret(heap)?
-                    .put_value(propname, heap)
-                    .or_else(crate::error::ignore_set_readonly)?;
+                self.left.assign(propname, heap)?;
 
                 match self.body.interpret(heap) {
                     Ok(_) => (),
                     Err(Exception::Jump(Jump::Continue(None))) => continue,
-                    Err(Exception::Jump(Jump::Break(None))) => {
-                        return Ok(Interpreted::VOID);
-                    }
-                    Err(e) => {
-                        return Err(e);
+                    Err(Exception::Jump(Jump::Continue(Some(target))))
+                        if Some(&target) == label =>
+                    {
+                        continue
                     }
+                    Err(Exception::Jump(Jump::Break(None))) => return Ok(()),
+                    Err(e) => return Err(e),
                 }
             }
 
             objref = heap.get(objref).proto;
         }
+        Ok(())
+    }
+}
+
+impl Interpretable for ForInStatement {
+    fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
+        self.do_loop(heap, None)?;
         Ok(Interpreted::VOID)
     }
 }
@@ -260,42 +429,190 @@ impl Interpretable for ContinueStatement {
     }
 }
 
-impl LabelStatement {
-    fn continue_loop(&self, heap: &mut Heap) -> JSResult<Interpreted> {
-        let LabelStatement(label, body) = self;
-        loop {
-            // must be a loop to continue
-            let loop_stmt = match &body.stmt {
-                Stmt::For(stmt) => stmt,
-                Stmt::ForIn(_) => todo!(),
-                // TODO: move this check into the parser?
-                _ => return Err(Exception::no_loop_for_continue_label(label.clone())),
-            };
+impl ForOfStatement {
+    /// Runs the loop; `continue label` is handled here for a loop with `label`.
+    fn do_loop(&self, heap: &mut Heap, label: Option<&Identifier>) -> JSResult<()> {
+        let iterable = self.right.evaluate(heap)?;
+        let mut iteration = Iteration::new(iterable, heap)?;
 
-            loop_stmt.do_update(heap)?;
-            let result = loop_stmt.do_loop(heap);
+        let bindings = self.left.iteration_bindings();
+        let this_ref = heap.interpret_this();
+        let outer_scope = heap.local_scope().unwrap_or(Heap::GLOBAL);
+        while let Some(value) = iteration.next(heap)? {
+            let result = if bindings.is_empty() {
+                (self.left.assign(value, heap)).and_then(|()| self.body.interpret(heap))
+            } else {
+                heap.enter_new_scope(this_ref, outer_scope, |heap| {
+                    heap.declare(bindings.iter().copied(), [].into_iter())?;
+                    self.left.assign(value.clone(), heap)?;
+                    self.body.interpret(heap)
+                })
+            };
             match result {
-                Err(Exception::Jump(Jump::Continue(Some(target)))) if &target == label => continue,
-                Err(Exception::Jump(Jump::Break(Some(target)))) if &target == label => break,
+                Ok(_) => (),
+                Err(Exception::Jump(Jump::Continue(None))) => (),
+                Err(Exception::Jump(Jump::Continue(Some(target)))) if Some(&target) == label => {}
+                Err(Exception::Jump(Jump::Break(None))) => break,
                 Err(e) => return Err(e),
-                Ok(()) => break,
             }
         }
+        Ok(())
+    }
+}
+
+impl Interpretable for ForOfStatement {
+    fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
+        self.do_loop(heap, None)?;
         Ok(Interpreted::VOID)
     }
 }
 
+/// The iteration protocol: an iterable has a `Symbol.iterator` method making an iterator,
+/// which is an object with a callable `.next()`.
+/// There are no symbols yet, the method is under the hidden key [`Heap::SYMBOL_ITERATOR`];
+/// objects without it are iterated if they have `.next()` themselves.
+/// Arrays and strings with their builtin iterators are stepped through in place.
+pub(crate) enum Iteration {
+    Array {
+        array_ref: JSRef,
+        index: usize,
+    },
+    Chars(vec::IntoIter<char>),
+    Iterator {
+        iterator_ref: JSRef,
+        next_ref: JSRef,
+    },
+}
+
+impl Iteration {
+    pub(crate) fn new(value: JSValue, heap: &mut Heap) -> JSResult<Iteration> {
+        let object_ref = match &value {
+            JSValue::String(_) => Heap::STRING_PROTO,
+            JSValue::Ref(r) if *r != Heap::NULL => *r,
+            _ => return Err(Exception::type_error(TypeError::NOT_ITERABLE, value)),
+        };
+
+        let method = match heap.lookup_protochain(object_ref, Heap::SYMBOL_ITERATOR) {
+            Some(place) => place.to_value(heap)?,
+            None => JSValue::Undefined,
+        };
+        let method_ref = match method {
+            JSValue::Ref(method_ref) if heap.get(method_ref).is_callable() => method_ref,
+            _ => return Self::from_iterator(value, heap),
+        };
+
+        let is_builtin = |proto: JSRef| {
+            let builtin = heap.get(proto).get_own_value(Heap::SYMBOL_ITERATOR);
+            builtin == Some(JSValue::Ref(method_ref))
+        };
+        let chars = match &value {
+            JSValue::String(s) => Some(s.as_str()),
+            _ => heap.get(object_ref).as_str(),
+        };
+        if let Some(s) = chars.filter(|_| is_builtin(Heap::STRING_PROTO)) {
+            return Ok(Iteration::Chars(s.chars().collect::<Vec<_>>().into_iter()));
+        }
+        if heap.get(object_ref).as_array().is_some() && is_builtin(Heap::ARRAY_PROTO) {
+            return Ok(Iteration::Array {
+                array_ref: object_ref,
+                index: 0,
+            });
+        }
+
+        let this_ref = value.objectify(heap);
+        let call = CallContext::from(vec![])
+            .with_this(this_ref)
+            .with_name("[Symbol.iterator]".into());
+        let iterator = heap.execute(method_ref, call)?.to_value(heap)?;
+        match iterator {
+            JSValue::Ref(r) if r != Heap::NULL => Self::from_iterator(iterator, heap),
+            _ => Err(Exception::type_error(TypeError::NOT_AN_OBJECT, iterator)),
+        }
+    }
+
+    /// Iterates an object with a callable `.next()`.
+    fn from_iterator(value: JSValue, heap: &mut Heap) -> JSResult<Iteration> {
+        let object_ref = match value {
+            JSValue::Ref(r) if r != Heap::NULL => r,
+            _ => return Err(Exception::type_error(TypeError::NOT_ITERABLE, value)),
+        };
+        let next = match heap.lookup_protochain(object_ref, "next") {
+            Some(place) => place.to_value(heap)?,
+            None => JSValue::Undefined,
+        };
+        match next {
+            JSValue::Ref(next_ref) if heap.get(next_ref).is_callable() => Ok(Iteration::Iterator {
+                iterator_ref: object_ref,
+                next_ref,
+            }),
+            _ => Err(Exception::type_error(TypeError::NOT_ITERABLE, value)),
+        }
+    }
+
+    /// The next value or `None` when done.
+    pub(crate) fn next(&mut self, heap: &mut Heap) -> JSResult<Option<JSValue>> {
+        match self {
+            Iteration::Array { array_ref, index } => {
+                // the length is checked on every step, the array may change
+                let array = heap.get(*array_ref).as_array();
+                let value = match array {
+                    Some(array) if *index < array.storage.len() => array.get(*index).cloned(),
+                    _ => return Ok(None),
+                };
+                *index += 1;
+                Ok(Some(value.unwrap_or(JSValue::Undefined)))
+            }
+            Iteration::Chars(chars) => Ok(chars.next().map(|c| JSValue::from(c.to_string()))),
+            Iteration::Iterator {
+                iterator_ref,
+                next_ref,
+            } => {
+                let call = CallContext::from(vec![])
+                    .with_this(*iterator_ref)
+                    .with_name("next".into());
+                let result = heap.execute(*next_ref, call)?.to_value(heap)?;
+                let result_ref = match result {
+                    JSValue::Ref(r) if r != Heap::NULL => r,
+                    _ => return Err(Exception::type_error(TypeError::NOT_AN_OBJECT, result)),
+                };
+                let done = Interpreted::member(result_ref, "done").to_value(heap)?;
+                if done.boolify(heap) {
+                    return Ok(None);
+                }
+                let value = Interpreted::member(result_ref, "value").to_value(heap)?;
+                Ok(Some(value))
+            }
+        }
+    }
+
+    /// All the remaining values, e.g. for `[...iterable]`.
+    pub(crate) fn collect(mut self, heap: &mut Heap) -> JSResult<Vec<JSValue>> {
+        let mut values = Vec::new();
+        while let Some(value) = self.next(heap)? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
 impl Interpretable for LabelStatement {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
         let LabelStatement(label, body) = self;
 
-        let result = body.interpret(heap);
+        // loops handle `continue label` themselves
+        let result = match &body.stmt {
+            Stmt::For(stmt) => stmt.run(heap, Some(label)).map(|()| Interpreted::VOID),
+            Stmt::ForIn(stmt) => stmt.do_loop(heap, Some(label)).map(|()| Interpreted::VOID),
+            Stmt::ForOf(stmt) => stmt.do_loop(heap, Some(label)).map(|()| Interpreted::VOID),
+            _ => body.interpret(heap),
+        };
         match result {
             Err(Exception::Jump(Jump::Break(Some(target)))) if &target == label => {
                 Ok(Interpreted::VOID)
             }
+            // the parser rejects this, unless the AST is built by hand
             Err(Exception::Jump(Jump::Continue(Some(target)))) if &target == label => {
-                self.continue_loop(heap)
+                Err(Exception::no_loop_for_continue_label(label.clone()))
             }
             _ => result,
         }
@@ -314,6 +631,10 @@ impl Interpretable for ReturnStatement {
         let ReturnStatement(argument) = self;
         let returned = match argument {
             None => Interpreted::VOID,
+            Some(Expression {
+                expr: Expr::Call(call_expr),
+                ..
+            }) => return call_expr.interpret_tail_call(heap),
             Some(argexpr) => argexpr.interpret(heap)?,
         };
         Err(Exception::Jump(Jump::Return(returned)))
@@ -339,53 +660,75 @@ impl CatchClause {
                 Exception::Jump(_) => {
                     panic!("Impossible to catch: {:?}", exc)
                 }
-                //Exception::ReferenceNotFound(ident) => { // TODO: ReferenceError
                 _ => {
-                    let message = format!("{:?}", exc);
+                    let class = exc.error_class();
+                    let proto = builtin::error::subclass_proto(class, heap);
+                    let message = exc.message(heap);
                     let args = vec![Interpreted::from(message)];
-                    let errval = builtin::error::error_constructor(
+                    let errval = builtin::error::construct_error(
+                        proto,
                         CallContext::from(args)
                             .with_this(this_ref)
-                            .with_name("Error".into()),
+                            .with_name(class.into()),
                         heap,
                     )?;
                     errval.to_value(heap)?
                 }
             };
 
-            heap.scope_mut()
-                .set_nonconf(self.param.0.as_str(), error_value)?;
+            for name in self.param.bound_names() {
+                heap.scope_mut()
+                    .set_nonconf(name.as_str(), JSValue::Undefined)?;
+            }
+            self.param.bind(error_value, heap)?;
             self.body.interpret(heap)
         })
     }
 }
 
 impl TryStatement {
-    fn run_finalizer(&self, heap: &mut Heap) -> Result<(), Exception> {
-        if let Some(finalizer) = self.finalizer.as_ref() {
-            finalizer.interpret(heap)?;
-        }
+    /// Runs `finally {}` after `result` of `try {}`/`catch {}`;
+    /// after an interrupt, it gets a step budget of its own to clean up.
+    fn run_finalizer(&self, result: &JSResult<Interpreted>, heap: &mut Heap) -> JSResult<()> {
+        let finalizer = match self.finalizer.as_ref() {
+            Some(finalizer) => finalizer,
+            None => return Ok(()),
+        };
+        match result {
+            Err(Exception::Interrupted(_)) => {
+                heap.with_fresh_steps(|heap| finalizer.interpret(heap))
+            }
+            _ => finalizer.interpret(heap),
+        }?;
         Ok(())
     }
 }
 
 impl Interpretable for TryStatement {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
-        let result = self.block.interpret(heap);
-        match &result {
-            Ok(_) | Err(Exception::Jump(_)) => {
-                self.run_finalizer(heap)?;
-                result
-            }
-            Err(exc) => {
-                let result = match &self.handler {
-                    None => result,
-                    Some(catch) => catch.interpret(exc, heap),
-                };
-                self.run_finalizer(heap)?;
-                result
-            }
+        let result = match call_in_place(self.block.interpret(heap), heap) {
+            Err(exc @ (Exception::Jump(_) | Exception::Interrupted(_))) => Err(exc),
+            Err(exc) => match &self.handler {
+                None => Err(exc),
+                Some(catch) => call_in_place(catch.interpret(&exc, heap), heap),
+            },
+            result => result,
+        };
+        self.run_finalizer(&result, heap)?;
+        result
+    }
+}
+
+/// `return f(...)` in `try {}` or `catch {}` is not a tail call:
+/// `f` must run before leaving them.
+fn call_in_place(result: JSResult<Interpreted>, heap: &mut Heap) -> JSResult<Interpreted> {
+    match result {
+        Err(Exception::Jump(Jump::TailCall(tail))) => {
+            let (func_ref, call) = tail.into_call();
+            let returned = heap.execute(func_ref, call)?;
+            Err(Exception::Jump(Jump::Return(returned)))
         }
+        result => result,
     }
 }
 
@@ -393,19 +736,63 @@ impl Interpretable for VariableDeclaration {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
         for decl in &self.declarations {
             if let Some(initexpr) = decl.init.as_ref() {
-                let name = &decl.name.0;
                 let value = initexpr.evaluate(heap)?;
-                match heap.lookup_var(name) {
-                    Some(Interpreted::Member { of, name }) => {
-                        heap.get_mut(of)
-                            .set_property(name.as_str(), value)
-                            .or_else(crate::error::ignore_set_readonly)?;
+                decl.name.bind(value, heap)?;
+            }
+        }
+        self.make_const(heap);
+        Ok(Interpreted::VOID)
+    }
+}
+
+impl VariableDeclaration {
+    /// `const` bindings in the current scope become read-only after initialization.
+    fn make_const(&self, heap: &mut Heap) {
+        if self.kind != DeclarationKind::Const {
+            return;
+        }
+        let names = (self.declarations.iter()).flat_map(|decl| decl.name.bound_names());
+        for name in names {
+            heap.scope_mut().make_const(name.as_str());
+        }
+    }
+}
+
+impl Pattern {
+    /// Assigns `value` to the already declared variables of the pattern.
+    fn bind(&self, value: JSValue, heap: &mut Heap) -> JSResult<()> {
+        match self {
+            Pattern::Identifier(name) => match heap.lookup_var(name.as_str()) {
+                Some(Interpreted::Member { of, name }) => (heap.get_mut(of))
+                    .set_property(name.as_str(), value)
+                    .or_else(crate::error::ignore_set_readonly),
+                _ => panic!("variable not declared: {}", name.as_str()),
+            },
+            Pattern::Array(elements) => {
+                let mut iteration = Iteration::new(value, heap)?;
+                for element in elements.iter() {
+                    let value = iteration.next(heap)?.unwrap_or(JSValue::Undefined);
+                    if let Some(pattern) = element {
+                        pattern.bind(value, heap)?;
                     }
-                    _ => panic!("variable not declared: {}", name),
                 }
+                Ok(())
+            }
+            Pattern::Object(properties) => {
+                let object_ref = match value {
+                    JSValue::Undefined | JSValue::Ref(Heap::NULL) => {
+                        return Err(Exception::type_error(TypeError::NOT_AN_OBJECT, value))
+                    }
+                    value => value.objectify(heap),
+                };
+                for (key, pattern) in properties.iter() {
+                    let name = key.to_name(heap)?;
+                    let value = Interpreted::member(object_ref, name.as_str()).to_value(heap)?;
+                    pattern.bind(value, heap)?;
+                }
+                Ok(())
             }
         }
-        Ok(Interpreted::VOID)
     }
 }
 
@@ -436,6 +823,54 @@ impl Interpretable for Expression {
             Expr::Function(expr) => expr.interpret(heap),
             Expr::New(expr) => expr.interpret(heap),
             Expr::This => Ok(Interpreted::from(heap.interpret_this())),
+            Expr::NewTarget => Ok(Interpreted::from(heap.interpret_new_target())),
+            Expr::Template(expr) => expr.interpret(heap),
+            Expr::TaggedTemplate(expr) => expr.interpret(heap),
+            Expr::Chain(expr) => {
+                let result = expr.interpret_chain(heap)?;
+                Ok(result.unwrap_or(Interpreted::VOID))
+            }
+            Expr::OptionalMember(_) | Expr::OptionalCall(_) => {
+                let result = self.interpret_chain(heap)?;
+                Ok(result.unwrap_or(Interpreted::VOID))
+            }
+            Expr::Spread(_) => Err(Exception::misplaced_spread()),
+        }
+    }
+}
+
+impl Expression {
+    /// Interprets a link of an optional chain, `None` means that the chain short-circuits.
+    fn interpret_chain(&self, heap: &mut Heap) -> JSResult<Option<Interpreted>> {
+        let is_nullish = |result: &Interpreted, heap: &mut Heap| -> JSResult<bool> {
+            let value = result.to_value(heap)?;
+            Ok(value == JSValue::Undefined || value == JSValue::NULL)
+        };
+        match &self.expr {
+            Expr::Member(member) | Expr::OptionalMember(member) => {
+                let optional = matches!(self.expr, Expr::OptionalMember(_));
+                let objresult = match member.0.interpret_chain(heap)? {
+                    Some(objresult) => objresult,
+                    None => return Ok(None),
+                };
+                if optional && is_nullish(&objresult, heap)? {
+                    return Ok(None);
+                }
+                member.interpret_of(objresult, heap).map(Some)
+            }
+            Expr::Call(call) | Expr::OptionalCall(call) => {
+                let optional = matches!(self.expr, Expr::OptionalCall(_));
+                let callee = match call.0.interpret_chain(heap)? {
+                    Some(callee) => callee,
+                    None => return Ok(None),
+                };
+                if optional && is_nullish(&callee, heap)? {
+                    return Ok(None);
+                }
+                let arguments = call.interpret_arguments(heap)?;
+                call.interpret_call(callee, arguments, heap).map(Some)
+            }
+            _ => self.interpret(heap).map(Some),
         }
     }
 }
@@ -483,52 +918,81 @@ impl Interpretable for LogicalExpression {
 impl BinOp {
     fn compute(&self, lval: &JSValue, rval: &JSValue, heap: &mut Heap) -> JSResult<JSValue> {
         Ok(match self {
-            BinOp::EqEq => JSValue::from(JSValue::loose_eq(lval, rval, heap)),
-            BinOp::NotEq => JSValue::from(!JSValue::loose_eq(lval, rval, heap)),
+            BinOp::EqEq => JSValue::from(JSValue::loose_eq(lval, rval, heap)?),
+            BinOp::NotEq => JSValue::from(!JSValue::loose_eq(lval, rval, heap)?),
             BinOp::EqEqEq => JSValue::from(JSValue::strict_eq(lval, rval, heap)),
             BinOp::NotEqEq => JSValue::from(!JSValue::strict_eq(lval, rval, heap)),
-            BinOp::Less => JSValue::compare(lval, rval, heap, |a, b| a < b, |a, b| a < b),
-            BinOp::Greater => JSValue::compare(lval, rval, heap, |a, b| a > b, |a, b| a > b),
-            BinOp::LtEq => JSValue::compare(lval, rval, heap, |a, b| a <= b, |a, b| a <= b),
-            BinOp::GtEq => JSValue::compare(lval, rval, heap, |a, b| a >= b, |a, b| a >= b),
+            BinOp::Less => JSValue::compare(lval, rval, heap, |a, b| a < b, |a, b| a < b)?,
+            BinOp::Greater => JSValue::compare(lval, rval, heap, |a, b| a > b, |a, b| a > b)?,
+            BinOp::LtEq => JSValue::compare(lval, rval, heap, |a, b| a <= b, |a, b| a <= b)?,
+            BinOp::GtEq => JSValue::compare(lval, rval, heap, |a, b| a >= b, |a, b| a >= b)?,
             BinOp::Plus => JSValue::plus(lval, rval, heap)?,
             BinOp::Minus => JSValue::minus(lval, rval, heap)?,
-            BinOp::Star => JSValue::numerically(lval, rval, heap, |a, b| a * b),
-            BinOp::Slash => JSValue::numerically(lval, rval, heap, |a, b| a / b),
-            BinOp::Percent => JSValue::numerically(lval, rval, heap, |a, b| a % b),
+            BinOp::Star => JSValue::arithmetic(lval, rval, heap, |a, b| a * b, |a, b| Ok(a * b))?,
+            BinOp::Slash => {
+                let bigdiv = |a: &JSBigInt, b: &JSBigInt| Ok(a / value::bigint_divisor(b)?);
+                JSValue::arithmetic(lval, rval, heap, |a, b| a / b, bigdiv)?
+            }
+            BinOp::Percent => {
+                let bigrem = |a: &JSBigInt, b: &JSBigInt| Ok(a % value::bigint_divisor(b)?);
+                JSValue::arithmetic(lval, rval, heap, |a, b| a % b, bigrem)?
+            }
+            BinOp::StarStar => {
+                let pow = builtin::math::pow;
+                JSValue::arithmetic(lval, rval, heap, pow, value::bigint_pow)?
+            }
             BinOp::Pipe => {
                 let bitor = |a, b| (a as i32 | b as i32) as f64;
-                JSValue::numerically(lval, rval, heap, bitor)
+                JSValue::numerically(lval, rval, heap, bitor)?
             }
             BinOp::Hat => {
                 let bitxor = |a, b| (a as i32 ^ b as i32) as f64;
-                JSValue::numerically(lval, rval, heap, bitxor)
+                JSValue::numerically(lval, rval, heap, bitxor)?
             }
             BinOp::Ampersand => {
                 let bitand = |a, b| (a as i32 & b as i32) as f64;
-                JSValue::numerically(lval, rval, heap, bitand)
+                JSValue::numerically(lval, rval, heap, bitand)?
             }
             BinOp::LtLt => {
                 let bitshl = |a, b| ((a as i32) << ((b as u32) & 0x1f) as i32) as f64;
-                JSValue::numerically(lval, rval, heap, bitshl)
+                JSValue::numerically(lval, rval, heap, bitshl)?
             }
             BinOp::GtGt => {
                 let bitshr = |a, b| ((a as i32) >> ((b as u32) & 0x1f) as i32) as f64;
-                JSValue::numerically(lval, rval, heap, bitshr)
+                JSValue::numerically(lval, rval, heap, bitshr)?
             }
             BinOp::GtGtGt => {
                 let bitshru = |a, b| ((a as u32) >> (b as u32) & 0x1f) as f64;
-                JSValue::numerically(lval, rval, heap, bitshru)
+                JSValue::numerically(lval, rval, heap, bitshru)?
             }
             BinOp::In => {
                 let prop = lval.stringify(heap)?;
-                let objref = rval.to_ref()?;
+                let objref = match rval {
+                    JSValue::Ref(r) if *r != Heap::NULL => *r,
+                    _ => {
+                        let what = Interpreted::from(rval.clone());
+                        return Err(Exception::attr_type_error(
+                            TypeError::IN_NOT_OBJECT,
+                            what,
+                            prop,
+                        ));
+                    }
+                };
                 let object = heap.get(objref);
                 let found = object.lookup_value(&prop, heap).is_some();
                 JSValue::from(found)
             }
             BinOp::InstanceOf => {
-                let constructor = rval.to_ref()?;
+                let constructor = match rval {
+                    JSValue::Ref(r) if heap.get(*r).is_callable() => *r,
+                    _ => {
+                        let what = Interpreted::from(rval.clone());
+                        return Err(Exception::type_error(
+                            TypeError::INSTANCEOF_NOT_CALLABLE,
+                            what,
+                        ));
+                    }
+                };
                 let found = match lval.to_ref() {
                     Err(_) => false,
                     Ok(objref) => objref.isinstance(constructor, heap)?,
@@ -553,20 +1017,29 @@ impl Interpretable for UnaryExpression {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
         let UnaryExpression(op, argexpr) = self;
         let arg = argexpr.interpret(heap)?;
-        let argvalue = || arg.to_value(heap);
-        let argnum = || argvalue().map(|val| val.numberify(heap).unwrap_or(f64::NAN));
+        let argvalue = |heap: &mut Heap| arg.to_value(heap);
+        let argnum = |heap: &mut Heap| argvalue(heap)?.to_number(heap);
+        // `-` and `~` keep bigints, `+1n` is a TypeError
+        let argnumeric =
+            |heap: &mut Heap| argvalue(heap)?.to_primitive(PrimitiveHint::Number, heap);
         let value = match op {
-            UnOp::Exclamation => JSValue::Bool(!argvalue()?.boolify(heap)),
-            UnOp::Minus => JSValue::Number(-argnum()?),
-            UnOp::Plus => JSValue::Number(argnum()?),
-            UnOp::Tilde => {
-                let num = argnum()?;
-                let num = if f64::is_nan(num) { 0.0 } else { num };
-                JSValue::from(-(1.0 + num))
-            }
+            UnOp::Exclamation => JSValue::Bool(!argvalue(heap)?.boolify(heap)),
+            UnOp::Minus => match argnumeric(heap)? {
+                JSValue::BigInt(n) => JSValue::BigInt(-n),
+                value => JSValue::Number(-value.to_number(heap)?),
+            },
+            UnOp::Plus => JSValue::Number(argnum(heap)?),
+            UnOp::Tilde => match argnumeric(heap)? {
+                JSValue::BigInt(n) => JSValue::BigInt(-(n + 1u8)),
+                value => {
+                    let num = value.to_number(heap)?;
+                    let num = if f64::is_nan(num) { 0.0 } else { num };
+                    JSValue::from(-(1.0 + num))
+                }
+            },
             UnOp::Void => JSValue::Undefined,
             UnOp::Typeof => JSValue::from(
-                argvalue()
+                argvalue(heap)
                     .map(|val| val.type_of(heap))
                     .unwrap_or("undefined"),
             ),
@@ -582,18 +1055,30 @@ impl Interpretable for UpdateExpression {
         let assignee = argexpr.interpret(heap)?;
 
         let oldvalue = assignee.to_value(heap)?;
-        let oldnum = oldvalue.numberify(heap).unwrap_or(f64::NAN);
-        let newnum = match op {
-            UpdOp::Increment => oldnum + 1.0,
-            UpdOp::Decrement => oldnum - 1.0,
+        let (oldvalue, newvalue) = match oldvalue.to_primitive(PrimitiveHint::Number, heap)? {
+            JSValue::BigInt(n) => {
+                let newbig = match op {
+                    UpdOp::Increment => &n + 1u8,
+                    UpdOp::Decrement => &n - 1u8,
+                };
+                (JSValue::BigInt(n), JSValue::BigInt(newbig))
+            }
+            value => {
+                let oldnum = value.to_number(heap)?;
+                let newnum = match op {
+                    UpdOp::Increment => oldnum + 1.0,
+                    UpdOp::Decrement => oldnum - 1.0,
+                };
+                (JSValue::from(oldnum), JSValue::from(newnum))
+            }
         };
 
         assignee
-            .put_value(JSValue::from(newnum), heap)
+            .put_value(newvalue.clone(), heap)
             .or_else(crate::error::ignore_set_readonly)?;
 
-        let resnum = if *prefix { newnum } else { oldnum };
-        Ok(Interpreted::from(resnum))
+        let result = if *prefix { newvalue } else { oldvalue };
+        Ok(Interpreted::from(result))
     }
 }
 
@@ -611,7 +1096,17 @@ impl Interpretable for SequenceExpression {
 
 impl Interpretable for MemberExpression {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
-        let MemberExpression(objexpr, propexpr, computed) = self;
+        // the object is evaluated before the property expression:
+        let objresult = self.0.interpret(heap)?;
+        self.interpret_of(objresult, heap)
+    }
+}
+
+impl MemberExpression {
+    /// Interprets the member access on an already interpreted object.
+    fn interpret_of(&self, objresult: Interpreted, heap: &mut Heap) -> JSResult<Interpreted> {
+        let MemberExpression(_, propexpr, computed) = self;
+        let objvalue = objresult.to_value(heap)?;
 
         // compute the name of the property:
         let propname = if *computed {
@@ -625,8 +1120,7 @@ impl Interpretable for MemberExpression {
         };
 
         // get the object reference for member computation:
-        let objresult = objexpr.interpret(heap)?;
-        let objref = match objresult.to_value(heap)? {
+        let objref = match objvalue {
             JSValue::Undefined => return Err(Exception::not_an_object(objresult)),
             value => value.objectify(heap),
         };
@@ -644,18 +1138,22 @@ impl Interpretable for MemberExpression {
     }
 }
 
+impl ObjectKey {
+    /// The property name, a computed `[key]` is evaluated.
+    fn to_name(&self, heap: &mut Heap) -> JSResult<JSString> {
+        match self {
+            ObjectKey::Identifier(ident) => Ok(ident.clone()),
+            ObjectKey::Computed(expr) => expr.evaluate(heap)?.stringify(heap),
+        }
+    }
+}
+
 impl Interpretable for ObjectExpression {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
         let mut object = JSObject::new();
 
         for (key, valexpr) in self.0.iter() {
-            let keyname = match key {
-                ObjectKey::Identifier(ident) => ident.clone(),
-                ObjectKey::Computed(expr) => {
-                    let result = expr.interpret(heap)?.to_value(heap)?;
-                    result.stringify(heap)?
-                }
-            };
+            let keyname = key.to_name(heap)?;
             let valresult = valexpr.interpret(heap)?;
             let value = valresult.to_value(heap)?;
             object.set_property(keyname.as_str(), value)?;
@@ -669,11 +1167,22 @@ impl Interpretable for ObjectExpression {
 impl Interpretable for ArrayExpression {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
         let ArrayExpression(exprs) = self;
-        let storage = (exprs.iter())
-            .map(|expr| expr.interpret(heap)?.to_value(heap))
-            .collect::<Result<Vec<JSValue>, Exception>>()?;
+        let mut storage = Vec::with_capacity(exprs.len());
+        for expr in exprs.iter() {
+            match expr {
+                Some(Expression {
+                    expr: Expr::Spread(iterable),
+                    ..
+                }) => {
+                    let values = Iteration::new(iterable.evaluate(heap)?, heap)?.collect(heap)?;
+                    storage.extend(values.into_iter().map(Some));
+                }
+                Some(expr) => storage.push(Some(expr.evaluate(heap)?)),
+                None => storage.push(None), // a hole
+            }
+        }
 
-        let object = JSObject::from_array(storage);
+        let object = JSObject::from_array_with_holes(storage);
         let object_ref = heap.alloc(object);
         Ok(Interpreted::from(object_ref))
     }
@@ -683,8 +1192,7 @@ impl Interpretable for AssignmentExpression {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
         let AssignmentExpression(leftexpr, modop, valexpr) = self;
 
-        let value = valexpr.evaluate(heap)?;
-
+        // The left-hand reference is evaluated first, e.g. `a[f()] = g()` calls `f()` before `g()`.
         // This can be:
         // - Interpreted::Member{ existing object, attribute }
         // - Interpreted::Member{ scope, existing variable }
@@ -693,9 +1201,11 @@ impl Interpretable for AssignmentExpression {
         let assignee = leftexpr.interpret(heap)?;
 
         let newvalue = match modop {
-            None => value,
+            None => valexpr.evaluate(heap)?,
             Some(op) => {
+                // `a += b` reads `a` before evaluating `b`
                 let oldvalue = assignee.to_value(heap)?;
+                let value = valexpr.evaluate(heap)?;
                 op.compute(&oldvalue, &value, heap)?
             }
         };
@@ -708,40 +1218,95 @@ impl Interpretable for AssignmentExpression {
 
 impl Interpretable for CallExpression {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
-        let CallExpression(callee_expr, argument_exprs) = self;
+        let arguments = self.interpret_arguments(heap)?;
+        let callee = self.0.interpret(heap)?;
+        self.interpret_call(callee, arguments, heap)
+    }
+}
 
-        let arguments = (argument_exprs.iter())
-            .map(|argexpr| argexpr.interpret(heap))
-            .collect::<Result<Vec<Interpreted>, Exception>>()?;
+impl CallExpression {
+    fn interpret_arguments(&self, heap: &mut Heap) -> JSResult<Vec<Interpreted>> {
+        interpret_arguments(&self.1, heap)
+    }
 
-        let callee = callee_expr.interpret(heap)?;
+    /// Calls an already interpreted `callee`.
+    fn interpret_call(
+        &self,
+        callee: Interpreted,
+        arguments: Vec<Interpreted>,
+        heap: &mut Heap,
+    ) -> JSResult<Interpreted> {
         let (func_ref, this_ref, name) = callee.resolve_call(heap)?;
 
-        heap.execute(
+        let mut call = CallContext::from(arguments)
+            .with_this(this_ref)
+            .with_name(name);
+        call.direct_eval = self.is_direct_eval();
+        heap.execute(func_ref, call)
+    }
+
+    /// Only a plain `eval(...)` call is a direct eval.
+    fn is_direct_eval(&self) -> bool {
+        match &self.0.expr {
+            Expr::Identifier(Identifier(id)) => id.as_str() == "eval",
+            _ => false,
+        }
+    }
+
+    /// `return f(...)`: evaluates the callee and the arguments, the call itself is left
+    /// to the caller's [`Closure::call`](crate::function::Closure::call) as [`Jump::TailCall`].
+    /// A direct `eval(...)` needs the current scope, so it is called in place.
+    fn interpret_tail_call(&self, heap: &mut Heap) -> JSResult<Interpreted> {
+        if self.is_direct_eval() {
+            let returned = self.interpret(heap)?;
+            return Err(Exception::Jump(Jump::Return(returned)));
+        }
+        let arguments = self.interpret_arguments(heap)?;
+        let callee = self.0.interpret(heap)?;
+        let (func_ref, this_ref, method_name) = callee.resolve_call(heap)?;
+        let arguments = (arguments.iter())
+            .map(|arg| arg.to_value(heap))
+            .collect::<JSResult<Vec<JSValue>>>()?;
+        let tail = TailCall {
             func_ref,
-            CallContext::from(arguments)
-                .with_this(this_ref)
-                .with_name(name),
-        )
+            this_ref,
+            method_name,
+            arguments,
+        };
+        Err(Exception::Jump(Jump::TailCall(Box::new(tail))))
+    }
+}
+
+/// Call arguments in order, `...iterable` becomes all of its values.
+fn interpret_arguments(exprs: &[Expression], heap: &mut Heap) -> JSResult<Vec<Interpreted>> {
+    let mut arguments = Vec::with_capacity(exprs.len());
+    for expr in exprs.iter() {
+        match &expr.expr {
+            Expr::Spread(iterable) => {
+                let values = Iteration::new(iterable.evaluate(heap)?, heap)?.collect(heap)?;
+                arguments.extend(values.into_iter().map(Interpreted::Value));
+            }
+            _ => arguments.push(expr.interpret(heap)?),
+        }
     }
+    Ok(arguments)
 }
 
 impl Interpretable for NewExpression {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
         let NewExpression(callee_expr, argument_exprs) = self;
 
-        let arguments = (argument_exprs.iter())
-            .map(|expr| expr.interpret(heap))
-            .collect::<Result<Vec<Interpreted>, Exception>>()?;
+        let arguments = interpret_arguments(argument_exprs, heap)?;
 
         let callee = callee_expr.interpret(heap)?;
         let funcref = callee.to_ref(heap)?;
-        let prototype_ref = (heap.get_mut(funcref))
-            .get_own_value("prototype")
-            .ok_or_else(|| {
-                Exception::attr_type_error(TypeError::CANNOT_GET_PROPERTY, callee, "prototype")
-            })?
-            .to_ref()?;
+        // bound functions construct their targets; only functions with `prototype` can construct
+        let constructor_ref = builtin::function::constructor_of(funcref, heap);
+        let constructor = heap.get(constructor_ref);
+        let prototype_ref = match constructor.get_own_value("prototype") {
+            Some(prototype) if constructor.is_callable() => prototype.to_ref()?,
+            _ => return Err(Exception::type_error(TypeError::NOT_CONSTRUCTOR, callee)),
+        };
 
         // allocate the object
         let mut object = JSObject::new();
@@ -750,12 +1315,11 @@ impl Interpretable for NewExpression {
         let object_ref = heap.alloc(object);
 
         // call its constructor
-        let result = heap.execute(
-            funcref,
-            CallContext::from(arguments)
-                .with_this(object_ref)
-                .with_name("<constructor>".into()),
-        )?;
+        let mut call = CallContext::from(arguments)
+            .with_this(object_ref)
+            .with_name("<constructor>".into());
+        call.new_target = Some(funcref);
+        let result = heap.execute(funcref, call)?;
         match result {
             Interpreted::Value(JSValue::Ref(r)) if r != Heap::NULL => Ok(result),
             _ => Ok(Interpreted::from(object_ref)),
@@ -763,6 +1327,57 @@ impl Interpretable for NewExpression {
     }
 }
 
+impl Interpretable for TemplateLiteral {
+    fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
+        let mut result = String::new();
+        for (i, quasi) in self.quasis.iter().enumerate() {
+            // untagged templates cannot have invalid escapes, this should have been a SyntaxError
+            let cooked = quasi.cooked.as_ref().unwrap_or(&quasi.raw);
+            result.push_str(cooked.as_str());
+            if let Some(expr) = self.expressions.get(i) {
+                let value = expr.evaluate(heap)?;
+                result.push_str(value.stringify(heap)?.as_str());
+            }
+        }
+        Ok(Interpreted::from(result.as_str()))
+    }
+}
+
+impl Interpretable for TaggedTemplateExpression {
+    fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
+        let TaggedTemplateExpression(tag_expr, quasi) = self;
+
+        let callee = tag_expr.interpret(heap)?;
+        let (func_ref, this_ref, name) = callee.resolve_call(heap)?;
+
+        let (cooked, raw): (Vec<JSValue>, Vec<JSValue>) = (quasi.quasis.iter())
+            .map(|elem| {
+                let cooked = match &elem.cooked {
+                    Some(s) => JSValue::from(s.clone()),
+                    None => JSValue::Undefined,
+                };
+                (cooked, JSValue::from(elem.raw.clone()))
+            })
+            .unzip();
+        let raw_ref = heap.alloc(JSObject::from_array(raw));
+        let strings_ref = heap.alloc(JSObject::from_array(cooked));
+        heap.get_mut(strings_ref).set_hidden("raw", raw_ref)?;
+
+        let mut arguments = vec![Interpreted::from(strings_ref)];
+        for expr in quasi.expressions.iter() {
+            let value = expr.evaluate(heap)?;
+            arguments.push(Interpreted::from(value));
+        }
+
+        heap.execute(
+            func_ref,
+            CallContext::from(arguments)
+                .with_this(this_ref)
+                .with_name(name),
+        )
+    }
+}
+
 impl Interpretable for FunctionExpression {
     fn interpret(&self, heap: &mut Heap) -> JSResult<Interpreted> {
         let closure = Closure {
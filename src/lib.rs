@@ -2,6 +2,9 @@
 #![doc = include_str!("../docs/DOC.md")]
 
 extern crate alloc;
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
 
 pub mod ast;
 mod builtin;
@@ -24,8 +27,10 @@ mod test;
 pub use ast::Program;
 pub use error::{Exception, JSResult, Jump};
 pub use function::{CallContext, HostFn, HostFunc};
-pub use heap::{Heap, JSRef};
+pub use heap::{
+    DebuggerHook, Heap, HeapBuilder, HeapSnapshot, JSRef, Limits, OutputSink, ParserHook,
+};
 pub use interpret::Interpretable;
 pub use object::{Interpreted, JSObject};
 pub use parse::{estree::ToESTree, HeapNode, SourceNode};
-pub use value::{JSNumber, JSString, JSValue, JSON};
+pub use value::{JSBigInt, JSNumber, JSString, JSValue, PrimitiveHint, JSON};
@@ -1,3 +1,5 @@
+use core::ops::Range;
+
 use bitflags::bitflags;
 use serde_json::json;
 
@@ -5,7 +7,9 @@ use crate::error::TypeError;
 use crate::prelude::*;
 
 use crate::function::{Closure, HostFn, HostFunc};
-use crate::{Exception, Heap, JSNumber, JSRef, JSResult, JSString, JSValue, JSON};
+use crate::{
+    builtin, CallContext, Exception, Heap, JSNumber, JSRef, JSResult, JSString, JSValue, JSON,
+};
 
 /// Javascript objects.
 /// A `JSObject` always has a `proto`.
@@ -15,8 +19,11 @@ use crate::{Exception, Heap, JSNumber, JSRef, JSResult, JSString, JSValue, JSON}
 pub struct JSObject {
     pub proto: JSRef,
     pub value: ObjectValue,
-    pub properties: HashMap<JSString, Property>, // TODO: StrKey
-                                                 // TODO: make fields private
+    /// In insertion order, see [`JSObject::ordered_properties`].
+    pub properties: IndexMap<JSString, Property>, // TODO: StrKey
+    // TODO: make fields private
+    /// Can new properties be added? See [`JSObject::prevent_extensions`].
+    extensible: bool,
 }
 
 impl JSObject {
@@ -24,7 +31,8 @@ impl JSObject {
         JSObject {
             proto: Heap::OBJECT_PROTO,
             value: ObjectValue::None,
-            properties: HashMap::new(),
+            properties: IndexMap::default(),
+            extensible: true,
         }
     }
 
@@ -33,7 +41,8 @@ impl JSObject {
         JSObject {
             proto: Heap::FUNCTION_PROTO,
             value: ObjectValue::from_func(f),
-            properties: HashMap::new(),
+            properties: IndexMap::default(),
+            extensible: true,
         }
     }
 
@@ -43,7 +52,8 @@ impl JSObject {
         let mut function_object = JSObject {
             proto: Heap::FUNCTION_PROTO,
             value: ObjectValue::Closure(closure),
-            properties: HashMap::new(),
+            properties: IndexMap::default(),
+            extensible: true,
         };
         function_object
             .set_nonconf("length", Content::from(params_count))
@@ -53,10 +63,19 @@ impl JSObject {
 
     /// Wrap the given vector into an Array.
     pub fn from_array(values: Vec<JSValue>) -> JSObject {
+        Self::from_array_with_holes(values.into_iter().map(Some).collect())
+    }
+
+    /// Wrap the given vector into an Array, `None` elements are holes: `[1, , 3]`.
+    pub fn from_array_with_holes(storage: Vec<Option<JSValue>>) -> JSObject {
         JSObject {
             proto: Heap::ARRAY_PROTO,
-            value: ObjectValue::Array(JSArray { storage: values }),
-            properties: HashMap::new(),
+            value: ObjectValue::Array(JSArray {
+                storage,
+                sealed: false,
+            }),
+            properties: IndexMap::default(),
+            extensible: true,
         }
     }
 
@@ -65,13 +84,24 @@ impl JSObject {
         JSObject {
             proto: Heap::BOOLEAN_PROTO,
             value: ObjectValue::Boolean(value),
-            properties: HashMap::new(),
+            properties: IndexMap::default(),
+            extensible: true,
+        }
+    }
+
+    /// Wrap the given number into Number
+    pub fn from_number(value: JSNumber) -> JSObject {
+        JSObject {
+            proto: Heap::NUMBER_PROTO,
+            value: ObjectValue::Number(value),
+            properties: IndexMap::default(),
+            extensible: true,
         }
     }
 
     /// Wrap the given string into String
     fn from_string(value: JSString) -> JSObject {
-        let mut properties = HashMap::new();
+        let mut properties = IndexMap::default();
         // TODO: String.prototype.length
         properties.insert(
             JSString::from("length"),
@@ -84,9 +114,80 @@ impl JSObject {
             proto: Heap::STRING_PROTO,
             value: ObjectValue::String(value),
             properties,
+            extensible: true,
         }
     }
 
+    pub fn is_extensible(&self) -> bool {
+        self.extensible
+    }
+
+    /// `Object.preventExtensions()`: no new properties can be added to `self` after this.
+    pub fn prevent_extensions(&mut self) {
+        self.extensible = false;
+    }
+
+    /// `Object.seal()`: prevents extensions and makes all own properties non-configurable,
+    /// array elements too.
+    pub fn seal(&mut self) {
+        self.prevent_extensions();
+        for property in self.properties.values_mut() {
+            property.access.remove(Access::CONF);
+        }
+        if let Some(array) = self.as_array_mut() {
+            array.sealed = true;
+        }
+    }
+
+    /// `Object.isSealed()`
+    pub fn is_sealed(&self) -> bool {
+        let elements_sealed = match self.as_array() {
+            Some(array) => array.sealed || array.storage.iter().all(Option::is_none),
+            None => true,
+        };
+        !self.extensible
+            && elements_sealed
+            && self.properties.values().all(|p| !p.access.configurable())
+    }
+
+    /// Own properties in the order of `Object.keys()`, `for...in` and `JSON.stringify()`:
+    /// integer keys ascending, then the other keys in insertion order.
+    /// Array elements are not properties: they go before all of these.
+    pub fn ordered_properties(&self) -> impl Iterator<Item = (&JSString, &Property)> {
+        let mut indexed = (self.properties.iter())
+            .filter_map(|(key, prop)| Some((array_index(key)?, (key, prop))))
+            .collect::<Vec<_>>();
+        indexed.sort_by_key(|(index, _)| *index);
+        let named = (self.properties.iter()).filter(|(key, _)| array_index(key).is_none());
+        indexed
+            .into_iter()
+            .map(|(_, property)| property)
+            .chain(named)
+    }
+
+    /// Indices of array elements and string characters, they are not in `properties`.
+    pub fn element_keys(&self) -> Vec<JSString> {
+        match &self.value {
+            ObjectValue::Array(array) => (0..array.storage.len())
+                .filter(|&i| array.get(i).is_some())
+                .map(|i| JSString::from(i.to_string()))
+                .collect(),
+            ObjectValue::String(s) => (0..s.chars().count())
+                .map(|i| JSString::from(i.to_string()))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Names of own enumerable properties, elements first: `Object.keys()`.
+    pub fn enumerable_keys(&self) -> Vec<JSString> {
+        let mut keys = self.element_keys();
+        let properties = self.ordered_properties();
+        let enumerable = properties.filter(|(_, prop)| prop.access.enumerable());
+        keys.extend(enumerable.map(|(key, _)| key.clone()));
+        keys
+    }
+
     /// It's roughly `Object.valueOf(self)`
     pub fn to_primitive(&self) -> Option<JSValue> {
         use ObjectValue::*;
@@ -131,20 +232,30 @@ impl JSObject {
     }
 
     /// Tries to get JSValue of the own property `name`.
-    /// This might call getters of the property.
+    /// Accessor properties are `undefined` here: calling a getter needs the heap,
+    /// see [`Interpreted::to_value`].
     pub fn get_own_value(&self, name: &str) -> Option<JSValue> {
+        self.get_own_content(name).map(|content| match content {
+            Content::Value(value) => value,
+            Content::Accessor { .. } => JSValue::Undefined,
+        })
+    }
+
+    /// The own property `name`: a value or accessors.
+    pub fn get_own_content(&self, name: &str) -> Option<Content> {
         // indexing
-        if let Ok(index) = usize::from_str(name) {
+        if let Some(index) = array_index(name) {
+            let index = index as usize;
             match &self.value {
                 ObjectValue::Array(array) => {
-                    if let Some(value) = array.storage.get(index) {
-                        return Some(value.clone());
+                    if let Some(value) = array.get(index) {
+                        return Some(Content::from(value.clone()));
                     }
                 }
                 ObjectValue::String(s) => {
                     // TODO: optimizie nth()'s sequential access
                     if let Some(c) = s.chars().nth(index) {
-                        return Some(JSValue::from(c.to_string()));
+                        return Some(Content::from(c.to_string()));
                     }
                 }
                 _ => (),
@@ -153,19 +264,17 @@ impl JSObject {
             // TODO: make this hack a regular getter once getters are ready
             match &self.value {
                 ObjectValue::Array(array) => {
-                    return Some(JSValue::from(array.storage.len() as i64))
+                    return Some(Content::from(array.storage.len() as i64))
                 }
                 ObjectValue::Closure(closure) => {
-                    return Some(JSValue::from(closure.function.params.len() as i64))
+                    return Some(Content::from(closure.function.params.len() as i64))
                 }
-                ObjectValue::String(s) => return Some(JSValue::from(s.len() as i64)),
+                ObjectValue::String(s) => return Some(Content::from(s.chars().count() as i64)),
                 _ => (),
             }
         }
 
-        self.properties.get(name).map(|prop| match &prop.content {
-            Content::Value(value) => value.clone(),
-        })
+        self.properties.get(name).map(|prop| prop.content.clone())
     }
 
     /// Check own and all inherited properties for `name` and returns the first found value.
@@ -182,6 +291,19 @@ impl JSObject {
         None
     }
 
+    /// Like [`JSObject::lookup_value`], but gives accessors as they are.
+    pub fn lookup_content(&self, name: &str, heap: &Heap) -> Option<Content> {
+        if let Some(content) = self.get_own_content(name) {
+            return Some(content);
+        }
+        for protoref in self.protochain(heap) {
+            if let Some(content) = heap.get(protoref).get_own_content(name) {
+                return Some(content);
+            }
+        }
+        None
+    }
+
     pub fn protochain<'a>(&self, heap: &'a Heap) -> ProtoChainIter<'a> {
         ProtoChainIter {
             heap,
@@ -196,14 +318,23 @@ impl JSObject {
         access: Access,
         even_nonwritable: bool,
     ) -> JSResult<()> {
-        if let Ok(index) = usize::from_str(name) {
+        if let Some(index) = array_index(name) {
+            let index = index as usize;
+            let extensible = self.extensible;
             if let Some(array) = self.as_array_mut() {
+                if index >= array.storage.len() && !extensible {
+                    return Err(Exception::attr_type_error(
+                        TypeError::NOT_EXTENSIBLE,
+                        "???",
+                        name,
+                    ));
+                }
                 // TODO: a[100500] will be interesting.
                 while array.storage.len() <= index {
-                    array.storage.push(JSValue::Undefined);
+                    array.storage.push(None);
                 }
                 let value = content.to_value()?;
-                array.storage[index] = value;
+                array.storage[index] = Some(value);
                 return Ok(());
             }
         }
@@ -219,6 +350,14 @@ impl JSObject {
                 }
 
                 if !(even_nonwritable || property.access.writable()) {
+                    if property.access.contains(Access::CONST) {
+                        let what = Interpreted::VOID;
+                        return Err(Exception::attr_type_error(
+                            TypeError::CONST_ASSIGN,
+                            what,
+                            name,
+                        ));
+                    }
                     let what = Interpreted::from("???"); // TODO
                     return Err(Exception::attr_type_error(
                         TypeError::SET_READONLY,
@@ -230,6 +369,13 @@ impl JSObject {
                 property.access = access;
                 property.content = content;
             }
+            None if !self.extensible => {
+                return Err(Exception::attr_type_error(
+                    TypeError::NOT_EXTENSIBLE,
+                    "???",
+                    name,
+                ));
+            }
             None => {
                 let prop = Property { content, access };
                 self.properties.insert(JSString::from(name), prop);
@@ -253,8 +399,8 @@ impl JSObject {
     }
 
     /// If `name` is a number and `self` is an Array, just set the array elemnt to `value`.
-    /// Otherwise: if the own property `name` does not exist, create it with `Access::all()` and
-    /// set to `Content::from(value)`.
+    /// Otherwise: if the own property `name` does not exist, create it writable, enumerable
+    /// and configurable, set to `Content::from(value)`.
     /// If the own property exists already, call `.set()` with its current access. This will fail
     /// to update non-writable properties.
     /// ES5: \[\[Put\]\] with strict error handing
@@ -264,7 +410,7 @@ impl JSObject {
     {
         let access = (self.properties.get(name))
             .map(|prop| prop.access)
-            .unwrap_or(Access::all());
+            .unwrap_or(Access::new(true, true, true));
         self.set(name, Content::from(value), access)
     }
 
@@ -275,7 +421,7 @@ impl JSObject {
     {
         let access = (self.properties.get(name))
             .map(|prop| prop.access)
-            .unwrap_or(Access::all());
+            .unwrap_or(Access::new(true, true, true));
         self.set_maybe_nonwritable(name, Content::from(value), access, true)
     }
 
@@ -288,6 +434,13 @@ impl JSObject {
         self.set(name, Content::from(value), Access::empty())
     }
 
+    /// Makes the own property `name` a `const` binding once it has its value.
+    pub(crate) fn make_const(&mut self, name: &str) {
+        if let Some(property) = self.properties.get_mut(name) {
+            property.access = (property.access - Access::WRITE) | Access::CONST;
+        }
+    }
+
     /// A shortcut for defining a non-enumerable property and setting its value.
     pub fn set_hidden<V>(&mut self, name: &str, value: V) -> JSResult<()>
     where
@@ -304,6 +457,19 @@ impl JSObject {
         self.set(name, Content::from(value), Access::NONCONF)
     }
 
+    /// Defines (or redefines) an accessor property with a getter and/or a setter.
+    pub fn set_accessors(
+        &mut self,
+        name: &str,
+        get: Option<JSRef>,
+        set: Option<JSRef>,
+        access: Access,
+    ) -> JSResult<()> {
+        let content = Content::Accessor { get, set };
+        // accessor properties do not have `writable`
+        self.set_maybe_nonwritable(name, content, access - Access::WRITE, true)
+    }
+
     /// A shortcut for defining a non-writable property and setting its value.
     pub fn set_readonly<V>(&mut self, name: &str, value: V) -> JSResult<()>
     where
@@ -315,7 +481,7 @@ impl JSObject {
     /// Create a `JSON` from this `JSObject`.
     pub fn to_json(&self, heap: &Heap) -> JSResult<JSON> {
         if let Some(array) = self.as_array() {
-            let jvals = (array.storage.iter())
+            let jvals = (array.values())
                 .map(|v| v.to_json(heap))
                 .collect::<JSResult<Vec<_>>>()?;
             return Ok(JSON::Array(jvals));
@@ -335,17 +501,6 @@ impl JSObject {
 
     /// Create a human-readable representation of contents of an Array or an Object.
     pub fn to_string(&self, heap: &mut Heap) -> JSResult<JSString> {
-        fn is_valid_identifier(s: &str) -> bool {
-            let is_start = |c: char| (c.is_alphabetic() || c == '_' || c == '$');
-
-            let mut it = s.chars();
-            if let Some(c) = it.next() {
-                is_start(c) && it.all(|c| is_start(c) || c.is_numeric())
-            } else {
-                false
-            }
-        }
-
         let is_array = self.as_array().is_some();
 
         let mut s = String::new();
@@ -353,7 +508,7 @@ impl JSObject {
 
         if let Some(array) = self.as_array() {
             s.push('[');
-            for item in array.storage.iter() {
+            for item in array.values() {
                 empty = false;
                 let itemstr = item.to_string(heap)?;
                 s.push_str(&itemstr);
@@ -368,7 +523,7 @@ impl JSObject {
             s.push('{');
         }
 
-        for (key, property) in self.properties.iter() {
+        for (key, property) in self.ordered_properties() {
             if !property.access.enumerable() {
                 continue;
             }
@@ -381,7 +536,10 @@ impl JSObject {
                 s.push_str(&skey);
             }
             s.push_str(": ");
-            let val = property.content.to_value()?.to_string(heap)?;
+            let val = match &property.content {
+                Content::Value(value) => value.to_string(heap)?,
+                accessor => JSString::from(accessor.describe_accessor()),
+            };
             s.push_str(&val);
             s.push(',');
             empty = false;
@@ -403,6 +561,24 @@ impl JSObject {
     }
 }
 
+/// `"42"` is an integer key, `"042"`, `"-1"`, `"4294967295"` are not.
+pub(crate) fn array_index(key: &str) -> Option<u32> {
+    let index = key.parse::<u32>().ok()?;
+    (index != u32::MAX && index.to_string() == key).then_some(index)
+}
+
+/// Can `s` be used as an object key without quoting?
+pub(crate) fn is_valid_identifier(s: &str) -> bool {
+    let is_start = |c: char| (c.is_alphabetic() || c == '_' || c == '$');
+
+    let mut it = s.chars();
+    if let Some(c) = it.next() {
+        is_start(c) && it.all(|c| is_start(c) || c.is_numeric())
+    } else {
+        false
+    }
+}
+
 impl<S> From<S> for JSObject
 where
     JSString: From<S>,
@@ -497,6 +673,8 @@ bitflags! {
         const ENUM = 0b001;
         const CONF = 0b010;
         const WRITE = 0b100;
+        /// a `const` binding: assigning to it is a TypeError, not ignored like other read-only properties
+        const CONST = 0b1000;
 
         const HIDDEN = Self::CONF.bits | Self::WRITE.bits;
         const READONLY = Self::ENUM.bits | Self::CONF.bits;
@@ -533,19 +711,32 @@ impl Access {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Content {
     Value(JSValue),
-    /*
-    Accessor{
+    Accessor {
         get: Option<JSRef>,
         set: Option<JSRef>,
     },
-    */
 }
 
 impl Content {
-    /// This might call getters of the property.
+    /// The value of a data property; accessors are `undefined` here,
+    /// [`Interpreted::to_value`] calls getters.
     pub fn to_value(&self) -> JSResult<JSValue> {
         match self {
             Self::Value(value) => Ok(value.clone()),
+            Self::Accessor { .. } => Ok(JSValue::Undefined),
+        }
+    }
+
+    /// How an accessor property is shown instead of its value, e.g. `[Getter/Setter]`.
+    pub fn describe_accessor(&self) -> &'static str {
+        match self {
+            Self::Accessor {
+                get: Some(_),
+                set: Some(_),
+            } => "[Getter/Setter]",
+            Self::Accessor { get: Some(_), .. } => "[Getter]",
+            Self::Accessor { set: Some(_), .. } => "[Setter]",
+            _ => "undefined",
         }
     }
 }
@@ -560,12 +751,41 @@ where
 }
 
 /// The underlying storage of an Array object.
+/// `None` is a hole: an index that is not set, but is counted by `length`.
 #[derive(Clone, Debug)]
 pub struct JSArray {
-    pub storage: Vec<JSValue>,
+    pub storage: Vec<Option<JSValue>>,
+    /// `Object.seal()`: the elements are non-configurable, they cannot be removed.
+    pub sealed: bool,
 }
 
-impl JSArray {}
+impl JSArray {
+    /// Removing the elements at `indices` from a sealed array is a TypeError
+    /// about the first existing one; holes can always be removed.
+    pub(crate) fn check_removable(&self, array_ref: JSRef, indices: Range<usize>) -> JSResult<()> {
+        if !self.sealed {
+            return Ok(());
+        }
+        match indices.into_iter().find(|&i| self.get(i).is_some()) {
+            Some(index) => Err(Exception::attr_type_error(
+                TypeError::NONCONFIGURABLE_PROPERTY,
+                array_ref,
+                index.to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// The element at `index`; `None` if it's a hole or out of bounds.
+    pub fn get(&self, index: usize) -> Option<&JSValue> {
+        self.storage.get(index).and_then(|value| value.as_ref())
+    }
+
+    /// Iterates over all elements, holes are `undefined`.
+    pub fn values(&self) -> impl Iterator<Item = JSValue> + '_ {
+        (self.storage.iter()).map(|value| value.clone().unwrap_or(JSValue::Undefined))
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Interpreted {
@@ -589,19 +809,25 @@ impl Interpreted {
     }
 
     /// If Interpreted::Value, unwrap;
-    /// if Interpreted::Member{of, name}, [`JSObject::lookup_value`] of `name` in `of`.
-    pub fn to_value(&self, heap: &Heap) -> JSResult<JSValue> {
+    /// if Interpreted::Member{of, name}, [`JSObject::lookup_value`] of `name` in `of`,
+    /// calling its getter if it is an accessor property (a setter-only one is `undefined`).
+    pub fn to_value(&self, heap: &mut Heap) -> JSResult<JSValue> {
         match self {
             Interpreted::Value(value) => Ok(value.clone()),
-            Interpreted::Member { of, name } => {
-                if let Some(value) = heap.get(*of).lookup_value(name, heap) {
-                    Ok(value)
-                } else if heap.is_scope(*of) {
-                    Err(Exception::no_reference(name.clone()))
-                } else {
-                    Ok(JSValue::Undefined)
+            Interpreted::Member { of, name } => match heap.get(*of).lookup_content(name, heap) {
+                Some(Content::Value(value)) => Ok(value),
+                Some(Content::Accessor {
+                    get: Some(getter), ..
+                }) => {
+                    let call = CallContext::from(vec![])
+                        .with_this(*of)
+                        .with_name(name.clone());
+                    heap.execute(getter, call)?.to_value(heap)
                 }
-            }
+                Some(Content::Accessor { get: None, .. }) => Ok(JSValue::Undefined),
+                None if heap.is_scope(*of) => Err(Exception::no_reference(name.clone())),
+                None => Ok(JSValue::Undefined),
+            },
         }
     }
 
@@ -621,9 +847,35 @@ impl Interpreted {
         }
     }
 
+    /// ES5: \[\[Put\]\] with strict error handling: assigning to a getter-only accessor
+    /// (own or inherited) or to a read-only property is a `TypeError`.
+    /// Sloppy mode assignments ignore it with [`crate::error::ignore_set_readonly`].
     pub fn put_value(&self, value: JSValue, heap: &mut Heap) -> JSResult<()> {
         match self {
             Interpreted::Member { of, name } => {
+                if let Some(Content::Accessor { set, .. }) =
+                    heap.get(*of).lookup_content(name, heap)
+                {
+                    let setter = set.ok_or_else(|| {
+                        Exception::attr_type_error(TypeError::SET_READONLY, *of, name.clone())
+                    })?;
+                    let call = CallContext::from(vec![Interpreted::from(value)])
+                        .with_this(*of)
+                        .with_name(name.clone());
+                    heap.execute(setter, call)?;
+                    return Ok(());
+                }
+                if let Some(array) = heap.get(*of).as_array() {
+                    if name.as_str() == "length" {
+                        return builtin::array::set_length(*of, value, heap);
+                    }
+                    match array_index(name.as_str()) {
+                        Some(index) if index as usize >= array.storage.len() => {
+                            heap.check_array_length(index as usize + 1)?;
+                        }
+                        _ => (),
+                    }
+                }
                 heap.get_mut(*of).set_property(name.as_str(), value)
             }
             _ => Err(Exception::type_error(
@@ -634,7 +886,7 @@ impl Interpreted {
     }
 
     /// Resolve self to: a callable JSRef, `this` JSRef and the method name.
-    pub fn resolve_call(&self, heap: &Heap) -> JSResult<(JSRef, JSRef, JSString)> {
+    pub fn resolve_call(&self, heap: &mut Heap) -> JSResult<(JSRef, JSRef, JSString)> {
         match self {
             Interpreted::Member { of: this_ref, name } => {
                 let of = match heap.lookup_protochain(*this_ref, name) {
@@ -644,16 +896,22 @@ impl Interpreted {
                         return Err(Exception::type_error(TypeError::NOT_CALLABLE, self.clone()))
                     }
                 };
-                let func_value = (heap.get(of).get_own_value(name)).ok_or_else(|| {
-                    Exception::type_error(TypeError::NOT_CALLABLE, Interpreted::member(of, name))
-                })?;
+                // a getter is called with the original `this`
+                let func_value = self.to_value(heap)?;
                 let func_ref = (func_value.to_ref()).map_err(|_| {
                     Exception::type_error(TypeError::NOT_CALLABLE, Interpreted::member(of, name))
                 })?;
-                Ok((func_ref, *this_ref, name.clone()))
+                // a variable in a scope is a free call, not a method call on the scope
+                let this_ref = match heap.is_scope(*this_ref) {
+                    true => Heap::GLOBAL,
+                    false => *this_ref,
+                };
+                Ok((func_ref, this_ref, name.clone()))
             }
             Interpreted::Value(JSValue::Ref(func_ref)) => {
-                let this_ref = Heap::GLOBAL; // TODO: figure out what is this
+                // a free call: sloppy mode `this` is the global object
+                // TODO: `undefined` in strict mode
+                let this_ref = Heap::GLOBAL;
                 Ok((*func_ref, this_ref, "<anonymous>".into()))
             }
             _ => Err(Exception::type_error(TypeError::NOT_CALLABLE, self.clone())),
@@ -667,12 +925,21 @@ impl Interpreted {
         match self {
             Interpreted::Member { of, name } => {
                 let object = heap.get_mut(*of);
+                if let (Some(index), Some(array)) = (array_index(name), object.as_array_mut()) {
+                    let index = index as usize;
+                    array.check_removable(*of, index..index + 1)?;
+                    // leaves a hole, the length stays
+                    if let Some(element) = array.storage.get_mut(index) {
+                        *element = None;
+                    }
+                    return Ok(());
+                }
                 let configurable = match object.properties.get(name) {
                     Some(p) => p.access.configurable(),
                     None => return Ok(()),
                 };
                 if configurable {
-                    object.properties.remove(name);
+                    object.properties.shift_remove(name);
                     Ok(())
                 } else {
                     Err(Exception::attr_type_error(
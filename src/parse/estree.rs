@@ -1,6 +1,6 @@
 use serde_json::json;
 
-use crate::{ast::*, prelude::*, JSON};
+use crate::{ast::*, object::is_valid_identifier, prelude::*, JSON};
 
 /// ToESTree de-parses an AST struct into its Esprima representation.
 pub trait ToESTree {
@@ -13,16 +13,32 @@ impl ToESTree for JSON {
     }
 }
 
+/// A missing node is `null`
+impl<T: ToESTree> ToESTree for Option<T> {
+    fn to_estree(&self) -> JSON {
+        match self {
+            Some(node) => node.to_estree(),
+            None => JSON::Null,
+        }
+    }
+}
+
+impl<T: ToESTree> ToESTree for [T] {
+    fn to_estree(&self) -> JSON {
+        JSON::from(self.iter().map(|node| node.to_estree()).collect::<Vec<_>>())
+    }
+}
+
 impl ToESTree for Program {
     fn to_estree(&self) -> JSON {
-        let body: Vec<JSON> = self.body.body.iter().map(|stmt| stmt.to_estree()).collect();
+        let body = self.body.body.to_estree();
         json!({"type": "Program", "sourceType": "script", "body": body})
     }
 }
 
 impl ToESTree for BlockStatement {
     fn to_estree(&self) -> JSON {
-        let body: Vec<JSON> = self.body.iter().map(|stmt| stmt.to_estree()).collect();
+        let body = self.body.to_estree();
         json!({"type": "BlockStatement", "body": body})
     }
 }
@@ -60,16 +76,123 @@ impl ToESTree for Statement {
     fn to_estree(&self) -> JSON {
         match &self.stmt {
             Stmt::Empty => json!({"type": "EmptyStatement"}),
+            Stmt::Block(block) => block.to_estree(),
             Stmt::Expr(stmt) => {
                 let jexpr = stmt.expression.to_estree();
                 json!({"type": "ExpressionStatement", "expression": jexpr})
             }
+            Stmt::If(stmt) => {
+                let test = stmt.test.to_estree();
+                let consequent = stmt.consequent.to_estree();
+                let alternate = stmt.alternate.to_estree();
+                json!({"type": "IfStatement", "test": test, "consequent": consequent, "alternate": alternate})
+            }
+            Stmt::Switch(stmt) => stmt.to_estree(),
+            Stmt::For(stmt) => stmt.to_estree(),
+            Stmt::ForIn(stmt) => {
+                let ForInStatement { left, right, body } = stmt.as_ref();
+                let (left, right, body) = (left.to_estree(), right.to_estree(), body.to_estree());
+                json!({"type": "ForInStatement", "left": left, "right": right, "body": body})
+            }
+            Stmt::ForOf(stmt) => {
+                let ForOfStatement { left, right, body } = stmt.as_ref();
+                let (left, right, body) = (left.to_estree(), right.to_estree(), body.to_estree());
+                json!({"type": "ForOfStatement", "left": left, "right": right, "body": body, "await": false})
+            }
+            Stmt::Return(ReturnStatement(argument)) => {
+                let argument = argument.to_estree();
+                json!({"type": "ReturnStatement", "argument": argument})
+            }
+            Stmt::Break(BreakStatement(label)) => {
+                let label = label.to_estree();
+                json!({"type": "BreakStatement", "label": label})
+            }
+            Stmt::Continue(ContinueStatement(label)) => {
+                let label = label.to_estree();
+                json!({"type": "ContinueStatement", "label": label})
+            }
+            Stmt::Label(stmt) => {
+                let LabelStatement(label, body) = stmt.as_ref();
+                let (label, body) = (label.to_estree(), body.to_estree());
+                json!({"type": "LabeledStatement", "label": label, "body": body})
+            }
+            Stmt::Throw(ThrowStatement(argument)) => {
+                let argument = argument.to_estree();
+                json!({"type": "ThrowStatement", "argument": argument})
+            }
+            Stmt::Try(stmt) => stmt.to_estree(),
+            Stmt::With(stmt) => {
+                let object = stmt.object.to_estree();
+                let body = stmt.body.to_estree();
+                json!({"type": "WithStatement", "object": object, "body": body})
+            }
+            Stmt::Debugger => json!({"type": "DebuggerStatement"}),
+            Stmt::Variable(vardecl) => vardecl.to_estree(),
+            Stmt::Function(funcdecl) => {
+                let mut jfunc = funcdecl.function.to_estree();
+                jfunc["type"] = JSON::from("FunctionDeclaration");
+                jfunc["id"] = funcdecl.id.to_estree();
+                jfunc
+            }
+        }
+    }
+}
+
+impl ToESTree for SwitchStatement {
+    fn to_estree(&self) -> JSON {
+        let discriminant = self.discriminant.to_estree();
+        let cases: Vec<JSON> = (self.cases.iter())
+            .map(|case| {
+                let test = case.test.to_estree();
+                let consequent = case.consequent.to_estree();
+                json!({"type": "SwitchCase", "test": test, "consequent": consequent})
+            })
+            .collect();
+        json!({"type": "SwitchStatement", "discriminant": discriminant, "cases": cases})
+    }
+}
+
+/// `while` and `do {} while` loops are parsed as [`ForStatement`]s too:
+/// a statement `init` other than a declaration or an expression must be a `do {} while` body.
+impl ToESTree for ForStatement {
+    fn to_estree(&self) -> JSON {
+        let test = self.test.to_estree();
+        let body = self.body.to_estree();
+        let init = match &self.init.stmt {
+            Stmt::Empty => JSON::Null,
             Stmt::Variable(vardecl) => vardecl.to_estree(),
-            _ => todo!(),
+            Stmt::Expr(stmt) => stmt.expression.to_estree(),
+            _ => return json!({"type": "DoWhileStatement", "test": test, "body": body}),
+        };
+        let update = self.update.to_estree();
+        json!({"type": "ForStatement", "init": init, "test": test, "update": update, "body": body})
+    }
+}
+
+impl ToESTree for ForInTarget {
+    fn to_estree(&self) -> JSON {
+        match self {
+            ForInTarget::Var(vardecl) => vardecl.to_estree(),
+            ForInTarget::Expr(expr) => expr.to_estree(),
         }
     }
 }
 
+impl ToESTree for TryStatement {
+    fn to_estree(&self) -> JSON {
+        let block = self.block.to_estree();
+        let handler = match &self.handler {
+            Some(CatchClause { param, body }) => {
+                let (param, body) = (param.to_estree(), body.to_estree());
+                json!({"type": "CatchClause", "param": param, "body": body})
+            }
+            None => JSON::Null,
+        };
+        let finalizer = self.finalizer.to_estree();
+        json!({"type": "TryStatement", "block": block, "handler": handler, "finalizer": finalizer})
+    }
+}
+
 impl ToESTree for Expression {
     fn to_estree(&self) -> JSON {
         match &self.expr {
@@ -82,14 +205,184 @@ impl ToESTree for Expression {
                 let op = op.to_estree();
                 json!({"type": "BinaryExpression", "left": left, "operator": op, "right": right})
             }
-            _ => todo!(),
+            Expr::LogicalOp(logop) => {
+                let LogicalExpression(left, op, right) = logop.as_ref();
+                let left = left.to_estree();
+                let right = right.to_estree();
+                let op = op.to_estree();
+                json!({"type": "LogicalExpression", "left": left, "operator": op, "right": right})
+            }
+            Expr::Call(call) => call.to_estree(),
+            Expr::OptionalCall(call) => {
+                let mut jcall = call.to_estree();
+                jcall["optional"] = JSON::from(true);
+                jcall
+            }
+            Expr::Array(ArrayExpression(elements)) => {
+                let elements = elements.to_estree();
+                json!({"type": "ArrayExpression", "elements": elements})
+            }
+            Expr::Object(object) => object.to_estree(),
+            Expr::Member(member) => member.to_estree(),
+            Expr::OptionalMember(member) => {
+                let mut jmember = member.to_estree();
+                jmember["optional"] = JSON::from(true);
+                jmember
+            }
+            Expr::Assign(assign) => {
+                let AssignmentExpression(left, modop, right) = assign.as_ref();
+                let op = match modop {
+                    None => "=".to_string(),
+                    Some(op) => format!("{}=", op.to_estree().as_str().unwrap()),
+                };
+                let (left, right) = (left.to_estree(), right.to_estree());
+                json!({"type": "AssignmentExpression", "operator": op, "left": left, "right": right})
+            }
+            Expr::Conditional(cond) => {
+                let test = cond.condexpr.to_estree();
+                let consequent = cond.thenexpr.to_estree();
+                let alternate = cond.elseexpr.to_estree();
+                json!({"type": "ConditionalExpression", "test": test, "consequent": consequent, "alternate": alternate})
+            }
+            Expr::Unary(unary) => {
+                let UnaryExpression(op, argument) = unary.as_ref();
+                let (op, argument) = (op.to_estree(), argument.to_estree());
+                json!({"type": "UnaryExpression", "operator": op, "argument": argument, "prefix": true})
+            }
+            Expr::Update(update) => {
+                let UpdateExpression(op, prefix, argument) = update.as_ref();
+                let (op, argument) = (op.to_estree(), argument.to_estree());
+                json!({"type": "UpdateExpression", "operator": op, "argument": argument, "prefix": prefix})
+            }
+            Expr::Sequence(SequenceExpression(exprs)) => {
+                let exprs = exprs.to_estree();
+                json!({"type": "SequenceExpression", "expressions": exprs})
+            }
+            Expr::Function(funcexpr) => funcexpr.to_estree(),
+            Expr::This => json!({"type": "ThisExpression"}),
+            Expr::NewTarget => {
+                let meta = Identifier::from("new").to_estree();
+                let property = Identifier::from("target").to_estree();
+                json!({"type": "MetaProperty", "meta": meta, "property": property})
+            }
+            Expr::New(new) => {
+                let NewExpression(callee, arguments) = new.as_ref();
+                let (callee, arguments) = (callee.to_estree(), arguments.to_estree());
+                json!({"type": "NewExpression", "callee": callee, "arguments": arguments})
+            }
+            Expr::Template(template) => template.to_estree(),
+            Expr::TaggedTemplate(tagged) => {
+                let TaggedTemplateExpression(tag, quasi) = tagged.as_ref();
+                let (tag, quasi) = (tag.to_estree(), quasi.to_estree());
+                json!({"type": "TaggedTemplateExpression", "tag": tag, "quasi": quasi})
+            }
+            Expr::Chain(expr) => {
+                let expr = expr.to_estree();
+                json!({"type": "ChainExpression", "expression": expr})
+            }
+            Expr::Spread(iterable) => {
+                let argument = iterable.to_estree();
+                json!({"type": "SpreadElement", "argument": argument})
+            }
+        }
+    }
+}
+
+impl ToESTree for CallExpression {
+    fn to_estree(&self) -> JSON {
+        let CallExpression(callee, arguments) = self;
+        let (callee, arguments) = (callee.to_estree(), arguments.to_estree());
+        json!({"type": "CallExpression", "callee": callee, "arguments": arguments, "optional": false})
+    }
+}
+
+impl ToESTree for MemberExpression {
+    fn to_estree(&self) -> JSON {
+        let MemberExpression(object, property, computed) = self;
+        let (object, property) = (object.to_estree(), property.to_estree());
+        json!({"type": "MemberExpression", "object": object, "property": property, "computed": computed, "optional": false})
+    }
+}
+
+impl ToESTree for ObjectExpression {
+    fn to_estree(&self) -> JSON {
+        let ObjectExpression(properties) = self;
+        let properties: Vec<JSON> = (properties.iter())
+            .map(|(key, value)| property(key, value.to_estree()))
+            .collect();
+        json!({"type": "ObjectExpression", "properties": properties})
+    }
+}
+
+/// A `Property` of an object literal or of an object pattern.
+fn property(key: &ObjectKey, value: JSON) -> JSON {
+    let (key, computed) = match key {
+        ObjectKey::Computed(expr) => (expr.to_estree(), true),
+        ObjectKey::Identifier(name) if is_valid_identifier(name.as_str()) => {
+            (Identifier::from(name.clone()).to_estree(), false)
+        }
+        ObjectKey::Identifier(name) => (Literal::from(name.clone()).to_estree(), false),
+    };
+    json!({
+        "type": "Property", "key": key, "computed": computed, "value": value,
+        "kind": "init", "method": false, "shorthand": false,
+    })
+}
+
+impl ToESTree for FunctionExpression {
+    fn to_estree(&self) -> JSON {
+        let func = self.func.as_ref();
+        let id = func.id.to_estree();
+        let params = func.params.to_estree();
+        let body = func.body.to_estree();
+        json!({
+            "type": "FunctionExpression", "id": id, "params": params, "body": body,
+            "generator": func.is_generator, "expression": func.is_expression, "async": func.is_async,
+        })
+    }
+}
+
+impl ToESTree for TemplateLiteral {
+    fn to_estree(&self) -> JSON {
+        let last = self.quasis.len().saturating_sub(1);
+        let quasis: Vec<JSON> = (self.quasis.iter().enumerate())
+            .map(|(i, quasi)| {
+                let cooked = quasi.cooked.as_ref().map(|s| s.as_str());
+                let value = json!({"cooked": cooked, "raw": quasi.raw.as_str()});
+                json!({"type": "TemplateElement", "value": value, "tail": i == last})
+            })
+            .collect();
+        let expressions = self.expressions.to_estree();
+        json!({"type": "TemplateLiteral", "quasis": quasis, "expressions": expressions})
+    }
+}
+
+impl ToESTree for Pattern {
+    fn to_estree(&self) -> JSON {
+        match self {
+            Pattern::Identifier(id) => id.to_estree(),
+            Pattern::Array(elements) => {
+                let elements = elements.to_estree();
+                json!({"type": "ArrayPattern", "elements": elements})
+            }
+            Pattern::Object(properties) => {
+                let properties: Vec<JSON> = (properties.iter())
+                    .map(|(key, pattern)| property(key, pattern.to_estree()))
+                    .collect();
+                json!({"type": "ObjectPattern", "properties": properties})
+            }
         }
     }
 }
 
 impl ToESTree for Literal {
     fn to_estree(&self) -> JSON {
-        json!({"type": "Literal", "value": self.to_json()})
+        match self {
+            Literal::BigInt(n) => {
+                json!({"type": "Literal", "value": null, "bigint": n.to_string()})
+            }
+            _ => json!({"type": "Literal", "value": self.to_json()}),
+        }
     }
 }
 
@@ -103,7 +396,59 @@ impl ToESTree for BinOp {
     fn to_estree(&self) -> JSON {
         JSON::from(match self {
             BinOp::Plus => "+",
-            _ => todo!(),
+            BinOp::Minus => "-",
+            BinOp::Star => "*",
+            BinOp::Slash => "/",
+            BinOp::Percent => "%",
+            BinOp::StarStar => "**",
+            BinOp::EqEq => "==",
+            BinOp::NotEq => "!=",
+            BinOp::EqEqEq => "===",
+            BinOp::NotEqEq => "!==",
+            BinOp::Less => "<",
+            BinOp::Greater => ">",
+            BinOp::LtEq => "<=",
+            BinOp::GtEq => ">=",
+            BinOp::Pipe => "|",
+            BinOp::Hat => "^",
+            BinOp::Ampersand => "&",
+            BinOp::LtLt => "<<",
+            BinOp::GtGt => ">>",
+            BinOp::GtGtGt => ">>>",
+            BinOp::In => "in",
+            BinOp::InstanceOf => "instanceof",
+        })
+    }
+}
+
+impl ToESTree for BoolOp {
+    fn to_estree(&self) -> JSON {
+        JSON::from(match self {
+            BoolOp::And => "&&",
+            BoolOp::Or => "||",
+        })
+    }
+}
+
+impl ToESTree for UnOp {
+    fn to_estree(&self) -> JSON {
+        JSON::from(match self {
+            UnOp::Exclamation => "!",
+            UnOp::Minus => "-",
+            UnOp::Plus => "+",
+            UnOp::Tilde => "~",
+            UnOp::Typeof => "typeof",
+            UnOp::Void => "void",
+            UnOp::Delete => "delete",
+        })
+    }
+}
+
+impl ToESTree for UpdOp {
+    fn to_estree(&self) -> JSON {
+        JSON::from(match self {
+            UpdOp::Increment => "++",
+            UpdOp::Decrement => "--",
         })
     }
 }
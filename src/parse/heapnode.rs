@@ -114,7 +114,7 @@ impl SourceNode for HeapNode {
         let array = (self.heap.get(arrref).as_array())
             .ok_or_else(|| ParseError::want("array", self.to_error()))?;
         let mut result = vec![];
-        for item in &array.storage {
+        for item in array.values() {
             let childref =
                 (item.to_ref()).map_err(|_| ParseError::want("objects", self.to_error()))?;
             let child = self.with_node(childref);
@@ -123,4 +123,8 @@ impl SourceNode for HeapNode {
         }
         Ok(result)
     }
+
+    fn is_null_node(&self) -> bool {
+        self.node == Heap::NULL
+    }
 }
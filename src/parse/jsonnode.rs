@@ -75,4 +75,8 @@ impl SourceNode for JSON {
             (jarray.as_array()).ok_or_else(|| ParseError::want("array", JSON::Null.to_error()))?;
         array.iter().map(func).collect()
     }
+
+    fn is_null_node(&self) -> bool {
+        self.is_null()
+    }
 }
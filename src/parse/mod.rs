@@ -3,6 +3,7 @@ mod heapnode;
 mod jsonnode;
 #[cfg(test)]
 mod test;
+mod validate;
 
 use crate::prelude::*;
 
@@ -10,7 +11,7 @@ use crate::ast::*; // yes, EVERYTHING.
 
 use crate::error::ParseError;
 use crate::source;
-use crate::JSON;
+use crate::{JSBigInt, JSON};
 
 pub use self::heapnode::HeapNode;
 
@@ -45,7 +46,9 @@ impl ParserContext {
         let in_variables = self.declared_variables.contains(name);
         match (kind, in_bindings, in_variables) {
             (DeclarationKind::Var, false, _) => self.declared_variables.insert(name.clone()),
-            (DeclarationKind::Let, false, _) => self.declared_bindings.insert(name.clone()),
+            (DeclarationKind::Let | DeclarationKind::Const, false, _) => {
+                self.declared_bindings.insert(name.clone())
+            }
             _ => return Err(ParseError::BindingRedeclared {}),
         };
         Ok(())
@@ -162,6 +165,9 @@ pub trait SourceNode: Sized {
     fn map_array<T, F>(&self, property: &str, func: F) -> ParseResult<Vec<T>>
     where
         F: FnMut(&Self) -> ParseResult<T>;
+
+    /// Is this node a `null`, e.g. an elision in `ArrayExpression.elements`?
+    fn is_null_node(&self) -> bool;
 }
 
 impl Program {
@@ -173,6 +179,7 @@ impl Program {
 
         let mut ctx = ParserContext::new();
         let body = BlockStatement::parse_from(source, &mut ctx)?;
+        validate::check_program_jumps(&body)?;
 
         let ParserContext {
             declared_variables: variables,
@@ -201,6 +208,7 @@ impl ParseFrom for Statement {
                 stmt.init = stmt.body.clone();
                 Stmt::For(Box::new(stmt))
             }
+            "DebuggerStatement" => Stmt::Debugger,
             "EmptyStatement" => Stmt::Empty,
             "ExpressionStatement" => Stmt::Expr(ExpressionStatement::parse_from(source, ctx)?),
             "ForStatement" | "WhileStatement" => {
@@ -211,6 +219,10 @@ impl ParseFrom for Statement {
                 let stmt = ForInStatement::parse_from(source, ctx)?;
                 Stmt::ForIn(Box::new(stmt))
             }
+            "ForOfStatement" => {
+                let stmt = ForOfStatement::parse_from(source, ctx)?;
+                Stmt::ForOf(Box::new(stmt))
+            }
             "FunctionDeclaration" => Stmt::Function(FunctionDeclaration::parse_from(source, ctx)?),
             "IfStatement" => {
                 let stmt = IfStatement::parse_from(source, ctx)?;
@@ -225,6 +237,10 @@ impl ParseFrom for Statement {
             "ThrowStatement" => Stmt::Throw(ThrowStatement::parse_from(source, ctx)?),
             "TryStatement" => Stmt::Try(TryStatement::parse_from(source, ctx)?),
             "VariableDeclaration" => Stmt::Variable(VariableDeclaration::parse_from(source, ctx)?),
+            "WithStatement" => {
+                let stmt = WithStatement::parse_from(source, ctx)?;
+                Stmt::With(Box::new(stmt))
+            }
             _ => {
                 return Err(ParseError::UnknownNodeType {
                     value: source.to_error(),
@@ -245,6 +261,16 @@ impl ParseFrom for BlockStatement {
     }
 }
 
+impl ParseFrom for WithStatement {
+    fn parse_from<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
+        source.expect_str("type", "WithStatement")?;
+
+        let object = source.map_node("object", |jobject| Expression::parse_from(jobject, ctx))?;
+        let body = source.map_node("body", |jbody| Statement::parse_from(jbody, ctx))?;
+        Ok(WithStatement { object, body })
+    }
+}
+
 impl ParseFrom for IfStatement {
     fn parse_from<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
         source.expect_str("type", "IfStatement")?;
@@ -297,6 +323,15 @@ impl ParseFrom for ForStatement {
         let dowhile_ok = source.expect_str("type", "DoWhileStatement");
         for_ok.or(while_ok).or(dowhile_ok)?;
 
+        // `let` bindings of the loop head are scoped to the loop itself,
+        // they are re-created for each iteration by the interpreter.
+        let (stmt, _bindings) = ctx.enter_block_scope(|ctx| Self::parse_loop(source, ctx))?;
+        Ok(stmt)
+    }
+}
+
+impl ForStatement {
+    fn parse_loop<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
         let init = source
             .map_opt_node("init", |jinit| {
                 if let Ok(var) = VariableDeclaration::parse_from(jinit, ctx) {
@@ -356,6 +391,35 @@ impl ParseFrom for ForInStatement {
     }
 }
 
+impl ParseFrom for ForOfStatement {
+    fn parse_from<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
+        source.expect_str("type", "ForOfStatement")?;
+
+        // `let`/`const` bindings of `left` are scoped to the loop
+        let (stmt, _bindings) = ctx.enter_block_scope(|ctx| {
+            let left = source.map_node("left", |jleft| {
+                if let Ok(vardecl) = VariableDeclaration::parse_from(jleft, ctx) {
+                    if vardecl.declarations.len() != 1 {
+                        return Err(ParseError::ForInMultipleVar());
+                    }
+                    Ok(ForInTarget::Var(vardecl))
+                } else if let Ok(expr) = Expression::parse_from(jleft, ctx) {
+                    Ok(ForInTarget::Expr(expr))
+                } else {
+                    Err(ParseError::UnexpectedValue {
+                        want: "VariableDeclaration | Pattern",
+                        value: jleft.to_error(),
+                    })
+                }
+            })?;
+            let right = source.map_node("right", |jright| Expression::parse_from(jright, ctx))?;
+            let body = source.map_node("body", |jbody| Statement::parse_from(jbody, ctx))?;
+            Ok(ForOfStatement { left, right, body })
+        })?;
+        Ok(stmt)
+    }
+}
+
 impl ParseFrom for BreakStatement {
     fn parse_from<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
         source.expect_str("type", "BreakStatement")?;
@@ -410,7 +474,7 @@ impl ParseFrom for TryStatement {
         let block = source.map_node("block", |jblock| BlockStatement::parse_from(jblock, ctx))?;
 
         let handler = source.map_opt_node("handler", |jhandler| {
-            let param = jhandler.map_node("param", |jparam| Identifier::parse_from(jparam, ctx))?;
+            let param = jhandler.map_node("param", |jparam| Pattern::parse_from(jparam, ctx))?;
             let body = jhandler.map_node("body", |jbody| BlockStatement::parse_from(jbody, ctx))?;
             Ok(CatchClause { param, body })
         })?;
@@ -432,7 +496,7 @@ impl ParseFrom for VariableDeclaration {
         source.expect_str("type", "VariableDeclaration")?;
 
         let kind = match source.get_str("kind")?.as_str() {
-            "const" => todo!("DeclarationKind::Const"),
+            "const" => DeclarationKind::Const,
             "let" => DeclarationKind::Let,
             "var" => DeclarationKind::Var,
             _ => {
@@ -445,14 +509,16 @@ impl ParseFrom for VariableDeclaration {
         let declarations = source.map_array("declarations", |decl| {
             decl.expect_str("type", "VariableDeclarator")?;
 
-            let name = decl.map_node("id", |jid| Identifier::parse_from(jid, ctx))?;
+            let name = decl.map_node("id", |jid| Pattern::parse_from(jid, ctx))?;
 
             let init = decl.map_opt_node("init", |jinit| {
                 let expr = Expression::parse_from(jinit, ctx)?;
                 Ok(Box::new(expr))
             })?;
 
-            ctx.remember_declaration(kind, &name)?;
+            for bound in name.bound_names() {
+                ctx.remember_declaration(kind, bound)?;
+            }
             Ok(VariableDeclarator { name, init })
         })?;
 
@@ -494,8 +560,13 @@ impl ParseFrom for Expression {
         let expr_type = source.get_str("type")?;
         let expr = match expr_type.as_str() {
             "ArrayExpression" => {
-                let elements =
-                    source.map_array("elements", |jelem| Expression::parse_from(jelem, ctx))?;
+                let elements = source.map_array("elements", |jelem| {
+                    // ESTree elisions (holes) are `null`
+                    match jelem.is_null_node() {
+                        true => Ok(None),
+                        false => Expression::parse_spreadable(jelem, ctx).map(Some),
+                    }
+                })?;
                 let expr = ArrayExpression(elements);
                 Expr::Array(expr)
             }
@@ -503,6 +574,10 @@ impl ParseFrom for Expression {
                 let expr = AssignmentExpression::parse_from(source, ctx)?;
                 Expr::Assign(Box::new(expr))
             }
+            "BigIntLiteral" => {
+                let lit = Literal::parse_from(source, ctx)?;
+                Expr::Literal(lit)
+            }
             "BinaryExpression" => {
                 let expr = BinaryExpression::parse_from(source, ctx)?;
                 Expr::BinaryOp(Box::new(expr))
@@ -510,9 +585,18 @@ impl ParseFrom for Expression {
             "CallExpression" => {
                 let callee =
                     source.map_node("callee", |jcallee| Expression::parse_from(jcallee, ctx))?;
-                let arguments =
-                    source.map_array("arguments", |jarg| Expression::parse_from(jarg, ctx))?;
-                Expr::Call(Box::new(CallExpression(callee, arguments)))
+                let arguments = source
+                    .map_array("arguments", |jarg| Expression::parse_spreadable(jarg, ctx))?;
+                let expr = Box::new(CallExpression(callee, arguments));
+                match source.get_bool("optional").unwrap_or(false) {
+                    true => Expr::OptionalCall(expr),
+                    false => Expr::Call(expr),
+                }
+            }
+            "ChainExpression" => {
+                let expr =
+                    source.map_node("expression", |jexpr| Expression::parse_from(jexpr, ctx))?;
+                Expr::Chain(Box::new(expr))
             }
             "ConditionalExpression" => {
                 let condexpr =
@@ -537,7 +621,7 @@ impl ParseFrom for Expression {
                 Expr::Identifier(expr)
             }
             "Literal" => {
-                let lit = source.get_literal("value")?;
+                let lit = Literal::parse_from(source, ctx)?;
                 Expr::Literal(lit)
             }
             "LogicalExpression" => {
@@ -550,14 +634,18 @@ impl ParseFrom for Expression {
                 let object = source.map_node("object", |jobj| Expression::parse_from(jobj, ctx))?;
                 let property =
                     source.map_node("property", |jprop| Expression::parse_from(jprop, ctx))?;
-                let expr = MemberExpression(object, property, computed);
-                Expr::Member(Box::new(expr))
+                let expr = Box::new(MemberExpression(object, property, computed));
+                // Esprima 4 does not know optional chaining and omits `optional`
+                match source.get_bool("optional").unwrap_or(false) {
+                    true => Expr::OptionalMember(expr),
+                    false => Expr::Member(expr),
+                }
             }
             "NewExpression" => {
                 let callee =
                     source.map_node("callee", |jcallee| Expression::parse_from(jcallee, ctx))?;
-                let arguments =
-                    source.map_array("arguments", |jarg| Expression::parse_from(jarg, ctx))?;
+                let arguments = source
+                    .map_array("arguments", |jarg| Expression::parse_spreadable(jarg, ctx))?;
                 let expr = NewExpression(callee, arguments);
                 Expr::New(Box::new(expr))
             }
@@ -569,6 +657,30 @@ impl ParseFrom for Expression {
                 let expr = SequenceExpression::parse_from(source, ctx)?;
                 Expr::Sequence(expr)
             }
+            "TaggedTemplateExpression" => {
+                let tag = source.map_node("tag", |jtag| Expression::parse_from(jtag, ctx))?;
+                let quasi =
+                    source.map_node("quasi", |jquasi| TemplateLiteral::parse_from(jquasi, ctx))?;
+                let expr = TaggedTemplateExpression(tag, quasi);
+                Expr::TaggedTemplate(Box::new(expr))
+            }
+            "TemplateLiteral" => {
+                let expr = TemplateLiteral::parse_from(source, ctx)?;
+                Expr::Template(expr)
+            }
+            "MetaProperty" => {
+                let meta = source.map_node("meta", |jmeta| jmeta.get_str("name"))?;
+                let property = source.map_node("property", |jprop| jprop.get_str("name"))?;
+                match (meta.as_str(), property.as_str()) {
+                    ("new", "target") => Expr::NewTarget,
+                    _ => {
+                        return Err(ParseError::UnexpectedValue {
+                            want: "new.target",
+                            value: source.to_error(),
+                        })
+                    }
+                }
+            }
             "ThisExpression" => Expr::This,
             "UnaryExpression" => {
                 let expr = UnaryExpression::parse_from(source, ctx)?;
@@ -588,6 +700,19 @@ impl ParseFrom for Expression {
     }
 }
 
+impl Expression {
+    /// An array element or a call argument, which can also be a `SpreadElement`.
+    fn parse_spreadable<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
+        if source.get_str("type")?.as_str() != "SpreadElement" {
+            return Expression::parse_from(source, ctx);
+        }
+        let loc = source.get_location().map(Box::new);
+        let iterable = source.map_node("argument", |jarg| Expression::parse_from(jarg, ctx))?;
+        let expr = Expr::Spread(Box::new(iterable));
+        Ok(Expression { expr, loc })
+    }
+}
+
 impl ParseFrom for Identifier {
     fn parse_from<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
         let name = source.get_str("name")?;
@@ -597,6 +722,62 @@ impl ParseFrom for Identifier {
     }
 }
 
+/// A `Literal`; bigints have their digits in `bigint`, e.g. `"42"` for `42n`,
+/// or they are Babel's `BigIntLiteral` with the digits in `value`.
+impl ParseFrom for Literal {
+    fn parse_from<S: SourceNode>(source: &S, _ctx: &mut ParserContext) -> ParseResult<Self> {
+        let digits = match source.get_str("type")?.as_str() {
+            "BigIntLiteral" => source.get_str("value")?,
+            _ => match source.get_str("bigint") {
+                Ok(digits) => digits,
+                Err(_) => return source.get_literal("value"),
+            },
+        };
+        let (radix, digits) = match digits.get(..2) {
+            Some("0x") | Some("0X") => (16, &digits[2..]),
+            Some("0o") | Some("0O") => (8, &digits[2..]),
+            Some("0b") | Some("0B") => (2, &digits[2..]),
+            _ => (10, digits.as_str()),
+        };
+        match JSBigInt::parse_bytes(digits.as_bytes(), radix) {
+            Some(n) => Ok(Literal::BigInt(n)),
+            None => Err(ParseError::want("bigint digits", source.to_error())),
+        }
+    }
+}
+
+impl ParseFrom for Pattern {
+    fn parse_from<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
+        let typ = source.get_str("type")?;
+        match typ.as_str() {
+            "Identifier" => Identifier::parse_from(source, ctx).map(Pattern::Identifier),
+            "ArrayPattern" => {
+                let elements = source.map_array("elements", |jelem| {
+                    // elisions are `null`
+                    match jelem.is_null_node() {
+                        true => Ok(None),
+                        false => Pattern::parse_from(jelem, ctx).map(Some),
+                    }
+                })?;
+                Ok(Pattern::Array(elements))
+            }
+            "ObjectPattern" => {
+                let properties = source.map_array("properties", |jprop| {
+                    jprop.expect_str("type", "Property")?;
+                    let key = ObjectKey::parse_from(jprop, ctx)?;
+                    let value = jprop.map_node("value", |jval| Pattern::parse_from(jval, ctx))?;
+                    Ok((key, value))
+                })?;
+                Ok(Pattern::Object(properties))
+            }
+            _ => Err(ParseError::UnexpectedValue {
+                want: "Identifier | ArrayPattern | ObjectPattern",
+                value: source.to_error(),
+            }),
+        }
+    }
+}
+
 impl ParseFrom for UnaryExpression {
     fn parse_from<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
         let jop = source.get_str("operator")?;
@@ -660,6 +841,7 @@ impl ParseFrom for BinaryExpression {
             "*" => BinOp::Star,
             "/" => BinOp::Slash,
             "%" => BinOp::Percent,
+            "**" => BinOp::StarStar,
             "==" => BinOp::EqEq,
             "===" => BinOp::EqEqEq,
             "!=" => BinOp::NotEq,
@@ -678,7 +860,7 @@ impl ParseFrom for BinaryExpression {
             "instanceof" => BinOp::InstanceOf,
             _ => {
                 return Err(ParseError::UnexpectedValue {
-                    want: "one of: + - * / % == === != < > <= >= instanceof | ^ & << >> >>>",
+                    want: "one of: + - * / % ** == === != < > <= >= instanceof | ^ & << >> >>>",
                     value: source.map_node("operator", |jop| Ok(jop.to_error()))?,
                 })
             }
@@ -717,6 +899,7 @@ impl ParseFrom for AssignmentExpression {
             "*=" => Some(BinOp::Star),
             "/=" => Some(BinOp::Slash),
             "%=" => Some(BinOp::Percent),
+            "**=" => Some(BinOp::StarStar),
             "<<=" => Some(BinOp::LtLt),
             ">>=" => Some(BinOp::GtGt),
             ">>>=" => Some(BinOp::GtGtGt),
@@ -725,7 +908,7 @@ impl ParseFrom for AssignmentExpression {
             "&=" => Some(BinOp::Ampersand),
             _ => {
                 return Err(ParseError::UnexpectedValue {
-                    want: "one of: = += -= *= /= %= <<= >>= >>>= |= ^= &=",
+                    want: "one of: = += -= *= /= %= **= <<= >>= >>>= |= ^= &=",
                     value: source.map_node("operator", |jop| Ok(jop.to_error()))?,
                 })
             }
@@ -738,6 +921,28 @@ impl ParseFrom for AssignmentExpression {
     }
 }
 
+/// The key of a `Property`, in an object literal or in an object pattern.
+impl ParseFrom for ObjectKey {
+    fn parse_from<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
+        let keyexpr = source.map_node("key", |jkey| Expression::parse_from(jkey, ctx))?;
+        if source.get_bool("computed")? {
+            return Ok(ObjectKey::Computed(keyexpr));
+        }
+        match keyexpr.expr {
+            Expr::Identifier(ident) => Ok(ObjectKey::Identifier(ident.0)),
+            Expr::Literal(Literal::BigInt(n)) => Ok(ObjectKey::Identifier(n.to_string().into())),
+            Expr::Literal(lit) => match lit.to_json().as_str() {
+                Some(val) => Ok(ObjectKey::Identifier(val.into())),
+                None => Ok(ObjectKey::Identifier(lit.to_string().into())),
+            },
+            _ => Err(ParseError::UnexpectedValue {
+                want: "Identifier|Literal",
+                value: source.to_error(),
+            }),
+        }
+    }
+}
+
 impl ParseFrom for ObjectExpression {
     fn parse_from<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
         source.expect_str("type", "ObjectExpression")?;
@@ -745,25 +950,7 @@ impl ParseFrom for ObjectExpression {
         let properties = source.map_array("properties", |jprop| {
             jprop.expect_str("type", "Property")?;
 
-            let keyexpr = jprop.map_node("key", |jkey| Expression::parse_from(jkey, ctx))?;
-            let key = if jprop.get_bool("computed")? {
-                ObjectKey::Computed(keyexpr)
-            } else {
-                match keyexpr.expr {
-                    Expr::Identifier(ident) => ObjectKey::Identifier(ident.0),
-                    Expr::Literal(lit) => match lit.to_json().as_str() {
-                        Some(val) => ObjectKey::Identifier(val.into()),
-                        None => ObjectKey::Identifier(lit.to_string().into()),
-                    },
-                    _ => {
-                        return Err(ParseError::UnexpectedValue {
-                            want: "Identifier|Literal",
-                            value: jprop.to_error(),
-                        })
-                    }
-                }
-            };
-
+            let key = ObjectKey::parse_from(jprop, ctx)?;
             let value = jprop.map_node("value", |jval| Expression::parse_from(jval, ctx))?;
 
             Ok((key, value))
@@ -773,6 +960,34 @@ impl ParseFrom for ObjectExpression {
     }
 }
 
+impl ParseFrom for TemplateLiteral {
+    fn parse_from<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
+        source.expect_str("type", "TemplateLiteral")?;
+
+        let quasis = source.map_array("quasis", |jquasi| {
+            jquasi.expect_str("type", "TemplateElement")?;
+            jquasi.map_node("value", |jvalue| {
+                let cooked = jvalue.get_str("cooked").ok();
+                let raw = jvalue.get_str("raw")?;
+                Ok(TemplateElement { cooked, raw })
+            })
+        })?;
+        let expressions =
+            source.map_array("expressions", |jexpr| Expression::parse_from(jexpr, ctx))?;
+        if quasis.len() != expressions.len() + 1 {
+            return Err(ParseError::UnexpectedValue {
+                want: "quasis.length == expressions.length + 1",
+                value: source.to_error(),
+            });
+        }
+
+        Ok(TemplateLiteral {
+            quasis,
+            expressions,
+        })
+    }
+}
+
 impl ParseFrom for FunctionExpression {
     fn parse_from<S: SourceNode>(source: &S, ctx: &mut ParserContext) -> ParseResult<Self> {
         let id: Option<Identifier> =
@@ -786,6 +1001,7 @@ impl ParseFrom for FunctionExpression {
         let body = source.map_node("body", |jbody| {
             BlockStatement::parse_from(jbody, &mut inner_ctx)
         })?;
+        validate::check_jumps(&body)?;
 
         let ParserContext {
             used_identifiers: mut free_variables,
@@ -0,0 +1,120 @@
+use crate::ast::*;
+
+use super::*;
+
+/// Checks that every `break`/`continue` in a function body
+/// has a target: an enclosing loop, `switch` or label.
+/// Nested functions are checked when they are parsed.
+pub(super) fn check_jumps(body: &BlockStatement) -> ParseResult<()> {
+    let mut targets = JumpTargets {
+        in_function: true,
+        ..JumpTargets::default()
+    };
+    targets.check_block(&body.body)
+}
+
+/// Like [`check_jumps`] for a whole program, which also must not `return`.
+pub(super) fn check_program_jumps(body: &BlockStatement) -> ParseResult<()> {
+    JumpTargets::default().check_block(&body.body)
+}
+
+/// What the statement being checked is nested in.
+#[derive(Default)]
+struct JumpTargets {
+    /// enclosing labels and whether each of them labels a loop
+    labels: Vec<(Identifier, bool)>,
+    in_loop: bool,
+    in_switch: bool,
+    in_function: bool,
+}
+
+impl JumpTargets {
+    fn check_block(&mut self, body: &[Statement]) -> ParseResult<()> {
+        body.iter().try_for_each(|stmt| self.check(stmt))
+    }
+
+    fn check_loop_body(&mut self, body: &Statement) -> ParseResult<()> {
+        let in_loop = core::mem::replace(&mut self.in_loop, true);
+        let result = self.check(body);
+        self.in_loop = in_loop;
+        result
+    }
+
+    fn find_label(&self, label: &Identifier) -> ParseResult<bool> {
+        let found = self.labels.iter().rev().find(|(name, _)| name == label);
+        let (_, is_loop) = found.ok_or_else(|| ParseError::UndefinedLabel(label.clone()))?;
+        Ok(*is_loop)
+    }
+
+    fn check(&mut self, stmt: &Statement) -> ParseResult<()> {
+        match &stmt.stmt {
+            Stmt::Block(block) => self.check_block(&block.body),
+            Stmt::If(stmt) => {
+                self.check(&stmt.consequent)?;
+                match &stmt.alternate {
+                    Some(alternate) => self.check(alternate),
+                    None => Ok(()),
+                }
+            }
+            Stmt::Switch(stmt) => {
+                let in_switch = core::mem::replace(&mut self.in_switch, true);
+                let mut cases = stmt.cases.iter();
+                let result = cases.try_for_each(|case| self.check_block(&case.consequent));
+                self.in_switch = in_switch;
+                result
+            }
+            Stmt::For(stmt) => {
+                // `init` is a copy of `body` for `do {} while ()`
+                self.check_loop_body(&stmt.init)?;
+                self.check_loop_body(&stmt.body)
+            }
+            Stmt::ForIn(stmt) => self.check_loop_body(&stmt.body),
+            Stmt::ForOf(stmt) => self.check_loop_body(&stmt.body),
+            Stmt::With(stmt) => self.check(&stmt.body),
+            Stmt::Try(stmt) => {
+                self.check_block(&stmt.block.body)?;
+                if let Some(handler) = &stmt.handler {
+                    self.check_block(&handler.body.body)?;
+                }
+                if let Some(finalizer) = &stmt.finalizer {
+                    self.check_block(&finalizer.body)?;
+                }
+                Ok(())
+            }
+            Stmt::Label(stmt) => {
+                let LabelStatement(label, body) = stmt.as_ref();
+                self.labels.push((label.clone(), labels_loop(body)));
+                let result = self.check(body);
+                self.labels.pop();
+                result
+            }
+            Stmt::Break(BreakStatement(None)) => match self.in_loop || self.in_switch {
+                true => Ok(()),
+                false => Err(ParseError::IllegalBreak()),
+            },
+            Stmt::Break(BreakStatement(Some(label))) => self.find_label(label).map(|_| ()),
+            Stmt::Continue(ContinueStatement(None)) => match self.in_loop {
+                true => Ok(()),
+                false => Err(ParseError::IllegalContinue()),
+            },
+            Stmt::Continue(ContinueStatement(Some(label))) => match self.find_label(label)? {
+                true => Ok(()),
+                false => Err(ParseError::ContinueLabelNotALoop(label.clone())),
+            },
+            Stmt::Return(_) => match self.in_function {
+                true => Ok(()),
+                false => Err(ParseError::IllegalReturn()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// `label: for (...)` or `outer: inner: while (...)`
+fn labels_loop(body: &Statement) -> bool {
+    match &body.stmt {
+        Stmt::For(_) | Stmt::ForIn(_) | Stmt::ForOf(_) => true,
+        Stmt::Label(stmt) => labels_loop(&stmt.1),
+        _ => false,
+    }
+}
@@ -12,8 +12,18 @@ pub use alloc::{
 #[cfg(feature = "std")]
 pub use std::collections::{HashMap, HashSet};
 
+#[cfg(feature = "std")]
+pub use indexmap::IndexMap;
+
 #[cfg(not(feature = "std"))]
 pub use hashbrown::{hash_map::HashMap, hash_set::HashSet};
 
+#[cfg(not(feature = "std"))]
+pub type IndexMap<K, V> = indexmap::IndexMap<K, V, hashbrown::hash_map::DefaultHashBuilder>;
+
+/// `f64::trunc()`, `f64::sqrt()` and alike are not in `core`: these come from `libm`.
+#[cfg(not(feature = "std"))]
+pub use num_traits::float::Float;
+
 #[allow(unused_imports)]
 pub use crate::{JSNumber, JSString, JSValue, JSON};
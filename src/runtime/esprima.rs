@@ -36,7 +36,10 @@ fn esprima_eval(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
         locflag: Heap::NULL,
     };
     let program = parser.parse(&code, heap)?;
-    program.interpret(heap)
+    match call.direct_eval {
+        true => program.interpret(heap),
+        false => heap.in_global_scope(|heap| program.interpret(heap)),
+    }
 }
 
 impl EsprimaParser {
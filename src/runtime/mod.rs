@@ -5,7 +5,7 @@ use core::str::Utf8Error;
 use std::io;
 
 use crate::function::HostFn;
-use crate::{error, Exception, Heap, JSString, JSValue, Program, JSON};
+use crate::{error, Exception, Heap, JSString, JSValue, ParserHook, Program, JSON};
 use crate::{prelude::*, CallContext, Interpreted, JSResult};
 
 pub use self::esprima::EsprimaParser;
@@ -134,19 +134,54 @@ pub trait Parser: fmt::Debug {
 ///
 pub struct Runtime {
     pub heap: Heap,
-    parser: Box<dyn Parser>,
+    parser: Rc<dyn Parser>,
+    sandboxed: bool,
 }
 
 impl Runtime {
     /// Creates a sljs runtime.
-    pub fn load(mut parser: Box<dyn Parser>) -> EvalResult<Self> {
-        let mut heap = Heap::new();
+    pub fn load(parser: Box<dyn Parser>) -> EvalResult<Self> {
+        Self::load_with_heap(parser, Heap::new())
+    }
+
+    /// Creates a sljs runtime on a custom `heap`, e.g. made by [`crate::HeapBuilder`].
+    pub fn load_with_heap(mut parser: Box<dyn Parser>, mut heap: Heap) -> EvalResult<Self> {
         parser.load(&mut heap)?;
+        let parser = Rc::<dyn Parser>::from(parser);
 
         let eval_ref = heap.alloc_func(parser.eval_func());
         heap.get_mut(Heap::GLOBAL).set_hidden("eval", eval_ref)?;
+        heap.set_parser_hook(Some(parser_hook(&parser)));
+
+        Ok(Runtime {
+            heap,
+            parser,
+            sandboxed: false,
+        })
+    }
+
+    /// In a sandboxed runtime `eval()` and `Function()` throw a TypeError instead of running code
+    /// through the parser (which might touch the filesystem or spawn processes).
+    /// Sandboxing also removes `process`, it does not come back when sandboxing is turned off:
+    /// see [`crate::HeapBuilder::process`].
+    pub fn set_sandboxed(&mut self, sandboxed: bool) -> EvalResult<()> {
+        if sandboxed {
+            Interpreted::member(Heap::GLOBAL, "process").delete(&mut self.heap)?;
+            self.heap.process = false;
+        }
+        let (eval_func, hook) = match sandboxed {
+            true => (sandboxed_eval as HostFn, None),
+            false => (self.parser.eval_func(), Some(parser_hook(&self.parser))),
+        };
+        let eval_ref = self.heap.alloc_func(eval_func);
+        (self.heap.get_mut(Heap::GLOBAL)).set_hidden("eval", eval_ref)?;
+        self.heap.set_parser_hook(hook);
+        self.sandboxed = sandboxed;
+        Ok(())
+    }
 
-        Ok(Runtime { heap, parser })
+    pub fn is_sandboxed(&self) -> bool {
+        self.sandboxed
     }
 
     /// Exposes the configured parser.
@@ -154,10 +189,22 @@ impl Runtime {
         self.parser.parse(input, &mut self.heap)
     }
 
-    /// Takes an `input` and evaluates it.
+    /// Takes an `input` and evaluates it, then runs the queued microtasks.
     pub fn evaluate(&mut self, input: &str) -> EvalResult<JSValue> {
         let program = self.parse(input)?;
-        self.heap.evaluate(&program).map_err(EvalError::Exception)
+        let result = self.heap.evaluate(&program)?;
+        self.heap.run_microtasks()?;
+        Ok(result)
+    }
+
+    /// Renders an error of [`Runtime::evaluate`] for the user,
+    /// uncaught exceptions with [`Heap::format_exception`].
+    pub fn format_error(&mut self, err: &EvalError) -> String {
+        match err {
+            EvalError::Exception(Exception::Syntax(_)) => err.to_string(),
+            EvalError::Exception(exc) => self.heap.format_exception(exc),
+            _ => err.to_string(),
+        }
     }
 
     /// Turn a [`JSValue`] into [`JSON`]
@@ -189,6 +236,16 @@ impl Runtime {
     }
 }
 
+fn parser_hook(parser: &Rc<dyn Parser>) -> ParserHook {
+    let parser = Rc::clone(parser);
+    Rc::new(move |input, heap| parser.parse(input, heap).map_err(Exception::from))
+}
+
+fn sandboxed_eval(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
+    let code = call.arg_value(0, heap)?;
+    Err(Exception::type_error(error::TypeError::EVAL_DISABLED, code))
+}
+
 /// Not-really-a-[`Parser`] implementation that just deserializes a JSON ESTree.
 #[derive(Debug)]
 pub struct JSONParser;
@@ -8,7 +8,7 @@ use std::process as proc;
 use crate::runtime::{self, EvalError, EvalResult};
 use crate::{
     error::ParseError, runtime::Parser, CallContext, Exception, Heap, Interpretable, Interpreted,
-    JSResult, Program, JSON,
+    JSObject, JSResult, JSValue, Program, JSON,
 };
 
 fn nodejs_eval(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
@@ -19,7 +19,31 @@ fn nodejs_eval(call: CallContext, heap: &mut Heap) -> JSResult<Interpreted> {
     let parser = NodejsParser { espath };
 
     let program = parser.parse(&code, heap)?;
-    program.interpret(heap)
+    match call.direct_eval {
+        true => program.interpret(heap),
+        false => heap.in_global_scope(|heap| program.interpret(heap)),
+    }
+}
+
+/// A minimal nodejs-like `process` object: `process.argv` and `process.env`.
+fn init_process(heap: &mut Heap) -> JSResult<()> {
+    let argv = env::args().map(JSValue::from).collect();
+    let argv_ref = heap.alloc(JSObject::from_array(argv));
+
+    let mut env_object = JSObject::new();
+    for (name, value) in env::vars_os() {
+        let value = value.to_string_lossy().to_string();
+        env_object.set_property(&name.to_string_lossy(), JSValue::from(value))?;
+    }
+    let env_ref = heap.alloc(env_object);
+
+    let mut process = JSObject::new();
+    process.set_property("argv", argv_ref)?;
+    process.set_property("env", env_ref)?;
+    let process_ref = heap.alloc(process);
+    heap.get_mut(Heap::GLOBAL)
+        .set_hidden("process", process_ref)?;
+    Ok(())
 }
 
 /// [`NodejsParser`] runs Esprima in an external nodejs process, consumes JSON AST.
@@ -86,7 +110,7 @@ impl NodejsParser {
 }
 
 impl runtime::Parser for NodejsParser {
-    fn load(&mut self, _: &mut Heap) -> EvalResult<()> {
+    fn load(&mut self, heap: &mut Heap) -> EvalResult<()> {
         let tmpdir = env::temp_dir().join(Self::TMPDIRNAME);
 
         let tmpdir = tmpdir.as_path();
@@ -103,6 +127,10 @@ impl runtime::Parser for NodejsParser {
         }
 
         self.espath = espath;
+
+        if heap.process {
+            init_process(heap)?;
+        }
         Ok(())
     }
 
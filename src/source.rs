@@ -31,12 +31,12 @@ impl Location {
 
     fn from_saved(object: &JSObject, heap: &Heap) -> Result<Location, Exception> {
         if let Some(array) = object.as_array() {
-            let line = array.storage[0].numberify(heap).unwrap() as u32;
-            let column = array.storage[1].numberify(heap).unwrap() as u32;
+            let line = array.get(0).unwrap().numberify(heap).unwrap() as u32;
+            let column = array.get(1).unwrap().numberify(heap).unwrap() as u32;
             let start = Position { line, column };
 
-            let line = array.storage[2].numberify(heap).unwrap() as u32;
-            let column = array.storage[3].numberify(heap).unwrap() as u32;
+            let line = array.get(2).unwrap().numberify(heap).unwrap() as u32;
+            let column = array.get(3).unwrap().numberify(heap).unwrap() as u32;
             let end = Position { line, column };
 
             Ok(Location { start, end })
@@ -49,6 +49,12 @@ impl Location {
     }
 }
 
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.start.line, self.start.column)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Document {
     _name: String,
@@ -77,7 +83,7 @@ impl<'heap> fmt::Display for Callstack<'heap> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.heap.loc.as_ref() {
             None => return Ok(()),
-            Some(loc) => writeln!(f, "{:?}", loc)?,
+            Some(loc) => writeln!(f, "    at {}", loc)?,
         };
 
         let mut scoperef = self.heap.local_scope().unwrap_or(Heap::NULL);
@@ -88,9 +94,9 @@ impl<'heap> fmt::Display for Callstack<'heap> {
                 .ok_or_else(|| Exception::Syntax(ParseError::no_attr(CALLER_LOCATION, JSON::Null)))
                 .map_err(|_| fmt::Error)?;
             if let Ok(loc) = Location::from_saved(self.heap.get(loc_ref), self.heap) {
-                writeln!(f, "   {:?}", loc)?;
+                writeln!(f, "    at {}", loc)?;
             } else {
-                writeln!(f, "   ???")?;
+                writeln!(f, "    at ???")?;
             }
 
             scoperef = match self.heap.get(scoperef).get_own_value(Heap::SAVED_SCOPE) {
@@ -103,19 +109,24 @@ impl<'heap> fmt::Display for Callstack<'heap> {
     }
 }
 
+/// The call stack of `heap`, the innermost location first, one per line.
+pub fn callstack(heap: &Heap) -> Result<String, Exception> {
+    let mut output = String::new();
+    write!(&mut output, "{}", Callstack { heap }).map_err(|_| {
+        let msg = "no caller location in the call stack";
+        Exception::UserThrown(JSValue::from(msg))
+    })?;
+    Ok(output)
+}
+
 #[cfg(feature = "std")]
 pub fn print_callstack(heap: &Heap) -> Result<(), Exception> {
     use std::io::Write;
 
-    let callstack = Callstack { heap };
+    let callstack = callstack(heap)?;
     let mut stderr = std::io::stderr();
     write!(&mut stderr, "{}", callstack).map_err(|e| {
         let msg = format!("{}", e);
         Exception::UserThrown(JSValue::from(msg))
     })
 }
-
-#[cfg(not(feature = "std"))]
-pub fn print_callstack(_heap: &Heap) -> Result<(), Exception> {
-    unimplemented!()
-}
@@ -1,4 +1,4 @@
-use crate::ast::expr;
+use crate::ast::{expr, stmt, BinOp, Program};
 
 use super::assert_eval;
 //use super::assert_exception;
@@ -38,3 +38,98 @@ fn binary_addition() {
     assert_eval!("5undefined", expr::add("5", expr::undefined()));
     assert_eval!((f64::NAN), expr::add(5, expr::undefined()));
 }
+
+#[test]
+fn builders() {
+    // var sum = 0;
+    // for (var i = 1; i <= 10; i = i + 1) {
+    //     if (i % 2 == 0) sum = sum + i; else sum = sum - 1;
+    // }
+    // while (sum > 20) sum = sum - 3;
+    // var twice = function (x) { var y = x + x; return y; };
+    // twice(sum)
+    let program = Program::from_stmts(vec![
+        stmt::var([("sum", expr::lit(0))].iter()).into(),
+        stmt::for_(
+            Some(stmt::var([("i", expr::lit(1))].iter()).into()),
+            Some(expr::binary(BinOp::LtEq, expr::id("i"), 10)),
+            Some(expr::assign(expr::id("i"), expr::add(expr::id("i"), 1))),
+            stmt::block(vec![stmt::if_(
+                expr::binary(
+                    BinOp::EqEq,
+                    expr::binary(BinOp::Percent, expr::id("i"), 2),
+                    0,
+                ),
+                stmt::expr(expr::assign(
+                    expr::id("sum"),
+                    expr::add(expr::id("sum"), expr::id("i")),
+                )),
+                Some(stmt::expr(expr::assign(
+                    expr::id("sum"),
+                    expr::binary(BinOp::Minus, expr::id("sum"), 1),
+                ))),
+            )])
+            .into(),
+        ),
+        stmt::while_(
+            expr::binary(BinOp::Greater, expr::id("sum"), 20),
+            stmt::expr(expr::assign(
+                expr::id("sum"),
+                expr::binary(BinOp::Minus, expr::id("sum"), 3),
+            )),
+        ),
+        stmt::var(
+            [(
+                "twice",
+                expr::func(
+                    &["x"],
+                    vec![
+                        stmt::var([("y", expr::add(expr::id("x"), expr::id("x")))].iter()).into(),
+                        stmt::return_(expr::id("y")),
+                    ],
+                ),
+            )]
+            .iter(),
+        )
+        .into(),
+        stmt::expr(expr::call(expr::id("twice"), vec![expr::id("sum")])),
+    ]);
+    // sum: 2 + 4 + 6 + 8 + 10 - 5 = 25, then 22, 19
+    assert_eval!(38.0, program);
+}
+
+/// A pre-parsed `Program` on a `Heap` is all it takes without `std`:
+/// ```sh
+/// $ cargo test -p sljs --no-default-features --lib no_std
+/// ```
+#[cfg(not(feature = "std"))]
+#[test]
+fn no_std_evaluate() {
+    use crate::{Heap, JSValue, JSON};
+
+    let estree: JSON = serde_json::from_str(
+        r#"{
+          "type": "Program",
+          "body": [{
+            "type": "ExpressionStatement",
+            "expression": {
+              "type": "BinaryExpression",
+              "operator": "+",
+              "left": { "type": "Literal", "value": 1 },
+              "right": { "type": "Literal", "value": 1 }
+            }
+          }]
+        }"#,
+    )
+    .expect("ESTree JSON");
+    let program = Program::parse_from(&estree).expect("Program::parse_from");
+
+    let mut heap = Heap::new();
+    assert_eq!(heap.evaluate(&program), Ok(JSValue::from(2)));
+
+    // `console` is there, writing to the stdout sink only
+    let log = expr::memb(expr::id("console"), "log");
+    let (result, output) = heap.evaluate_captured(&expr::call(log, vec![expr::lit("hi")]));
+    assert_eq!(result, Ok(JSValue::Undefined));
+    assert_eq!(output, "hi\n");
+}
@@ -44,6 +44,7 @@ pub(crate) use assert_exception;
 #[test]
 fn struct_sizes() {
     use crate::object::*;
+    use crate::prelude::*;
     use crate::*;
     use std::mem::size_of;
 
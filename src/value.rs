@@ -1,11 +1,18 @@
-use core::{borrow::Borrow, convert::Infallible, ops::Deref, str::Chars};
+use core::{borrow::Borrow, cmp::Ordering, convert::Infallible, ops::Deref, str::Chars};
 
+use num_traits::{FromPrimitive, Signed, ToPrimitive, Zero};
+
+use crate::builtin::number;
+use crate::error::{RangeError, TypeError};
 use crate::{prelude::*, CallContext, Exception, Heap, Interpreted, JSObject, JSRef, JSResult};
 
 pub type JSON = serde_json::Value;
 
 pub type JSNumber = f64;
 
+/// `BigInt` values: integers of arbitrary precision, `42n`.
+pub type JSBigInt = num_bigint::BigInt;
+
 /// A Javascript string value.
 ///
 /// Why not `std::string::String`?
@@ -108,12 +115,24 @@ mod test_strings {
     }
 }
 
+/// Which conversion [`JSValue::to_primitive`] tries first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrimitiveHint {
+    /// `valueOf()`, then `toString()`: used by `+` and `==`
+    Default,
+    /// `valueOf()`, then `toString()`
+    Number,
+    /// `toString()`, then `valueOf()`
+    String,
+}
+
 /// A `JSValue` is either a primitive value or a reference to an object.
 #[derive(Debug, Clone, PartialEq)]
 pub enum JSValue {
     Undefined,
     Bool(bool),
     Number(JSNumber),
+    BigInt(JSBigInt),
     String(JSString),
     //Symbol(String)
     Ref(JSRef),
@@ -141,6 +160,10 @@ impl JSValue {
             JSValue::Undefined => Ok(JSON::Null),
             JSValue::Bool(b) => Ok(JSON::from(*b)),
             JSValue::Number(n) => Ok(JSON::from(*n)),
+            JSValue::BigInt(_) => Err(Exception::type_error(
+                TypeError::BIGINT_TO_JSON,
+                self.clone(),
+            )),
             JSValue::String(s) => Ok(JSON::from(s.as_str())),
             JSValue::Ref(Heap::NULL) => Ok(JSON::Null),
             JSValue::Ref(href) => heap.get(*href).to_json(heap),
@@ -181,6 +204,7 @@ impl JSValue {
                 let jstr = JSON::from(s.as_str());
                 Ok(JSString::from(jstr.to_string()))
             }
+            JSValue::BigInt(n) => Ok(JSString::from(format!("{}n", n))),
             JSValue::Ref(heapref) => {
                 // without `.clone()` `heap` cannot be borrowed in both places
                 heap.get(*heapref).clone().to_string(heap)
@@ -196,7 +220,8 @@ impl JSValue {
         match self {
             JSValue::Undefined => Ok("undefined".into()),
             JSValue::Bool(b) => Ok(b.to_string().into()),
-            JSValue::Number(n) => Ok(n.to_string().into()),
+            JSValue::Number(n) => Ok(number::to_decimal_string(*n).into()),
+            JSValue::BigInt(n) => Ok(n.to_string().into()),
             JSValue::String(s) => Ok(s.clone()),
             JSValue::Ref(r) if r == &Heap::NULL => Ok(JSString::from("null")),
             JSValue::Ref(r) => match heap.lookup_protochain(*r, "toString") {
@@ -204,12 +229,9 @@ impl JSValue {
                     let funcref = to_string.to_ref(heap)?;
                     let result = heap.execute(
                         funcref,
-                        CallContext {
-                            this_ref: *r,
-                            method_name: "toString".into(),
-                            arguments: vec![],
-                            loc: None,
-                        },
+                        CallContext::from(vec![])
+                            .with_this(*r)
+                            .with_name("toString".into()),
                     )?;
                     Ok(result.to_value(heap)?.stringify(heap)?)
                 }
@@ -218,6 +240,53 @@ impl JSValue {
         }
     }
 
+    /// ES5: ToPrimitive. Primitive values are returned as they are, objects are asked
+    /// for `.valueOf()`/`.toString()` in the order of `hint`.
+    /// The first method that is callable and returns a primitive value wins.
+    pub fn to_primitive(&self, hint: PrimitiveHint, heap: &mut Heap) -> JSResult<JSValue> {
+        let objref = match self {
+            JSValue::Ref(r) if *r != Heap::NULL => *r,
+            _ => return Ok(self.clone()),
+        };
+        let methods = match hint {
+            PrimitiveHint::Default | PrimitiveHint::Number => ["valueOf", "toString"],
+            PrimitiveHint::String => ["toString", "valueOf"],
+        };
+        for method in methods {
+            let funcref = match heap.lookup_protochain(objref, method) {
+                Some(place) => place.to_value(heap)?,
+                None => continue,
+            };
+            let funcref = match funcref {
+                JSValue::Ref(r) if heap.get(r).is_callable() => r,
+                _ => continue,
+            };
+            let call = CallContext::from(vec![])
+                .with_this(objref)
+                .with_name(method.into());
+            let result = heap.execute(funcref, call)?.to_value(heap)?;
+            match result {
+                JSValue::Ref(r) if r != Heap::NULL => continue,
+                primitive => return Ok(primitive),
+            }
+        }
+        Err(Exception::type_error(TypeError::NO_PRIMITIVE, objref))
+    }
+
+    /// ES5: ToNumber, i.e. `+value` in JavaScript.
+    /// Objects are converted with [`JSValue::to_primitive`] first.
+    /// Bigints are not converted implicitly, this is a TypeError.
+    pub fn to_number(&self, heap: &mut Heap) -> JSResult<JSNumber> {
+        let primitive = self.to_primitive(PrimitiveHint::Number, heap)?;
+        if let JSValue::BigInt(_) = primitive {
+            return Err(Exception::type_error(
+                TypeError::BIGINT_TO_NUMBER,
+                primitive,
+            ));
+        }
+        Ok(primitive.numberify(heap).unwrap_or(f64::NAN))
+    }
+
     /// numberify() tries to make everything into a numeric value
     /// for evalation in a numeric context.
     /// It is slightly more strict than `+value` in JavaScript: only
@@ -227,15 +296,17 @@ impl JSValue {
             JSValue::Undefined => None, // Some(f64::NAN),
             JSValue::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
             JSValue::Number(n) => Some(*n),
-            JSValue::String(s) => s.as_str().parse::<JSNumber>().ok(),
+            JSValue::BigInt(_) => None,
+            JSValue::String(s) => parse_number(s.as_str()),
             JSValue::Ref(Heap::NULL) => Some(0.0),
             JSValue::Ref(r) => {
                 let object = heap.get(*r);
                 if let Some(array) = object.as_array() {
                     match &array.storage[..] {
-                        [] => Some(0.0),              // +[]  == 0
-                        [val] => val.numberify(heap), // +[x] == x
-                        _ => None,                    // +[x, y, ..] == NaN
+                        [] => Some(0.0),                    // +[]  == 0
+                        [None] => Some(0.0),                // +[,] == 0
+                        [Some(val)] => val.numberify(heap), // +[x] == x
+                        _ => None,                          // +[x, y, ..] == NaN
                     }
                 } else {
                     object.to_primitive().and_then(|v| v.numberify(heap))
@@ -250,6 +321,7 @@ impl JSValue {
         match self {
             JSValue::Undefined => false,
             JSValue::String(s) => !s.as_str().is_empty(),
+            JSValue::BigInt(n) => !n.is_zero(),
             JSValue::Ref(Heap::NULL) => false,
             JSValue::Ref(_) => true,
             _ => {
@@ -265,15 +337,14 @@ impl JSValue {
     /// objectify() wraps a primitive into its object:
     /// - `undefined` becomes `null`
     /// - `bool`/`number`/`string` becomes `Boolean`/`Number`/`String`
+    /// - `bigint` becomes an empty object: there is no `BigInt.prototype` yet
     /// - objects just return their reference.
     pub fn objectify(&self, heap: &mut Heap) -> JSRef {
         match self {
             JSValue::Undefined => Heap::NULL,
             JSValue::Bool(b) => heap.alloc(JSObject::from_bool(*b)),
-            JSValue::Number(_n) => {
-                let _ = crate::source::print_callstack(heap);
-                todo!(); // TODO: Number object
-            }
+            JSValue::Number(n) => heap.alloc(JSObject::from_number(*n)),
+            JSValue::BigInt(_) => heap.alloc(JSObject::new()),
             JSValue::String(s) => heap.alloc(JSObject::from(s.clone())),
             JSValue::Ref(r) => *r,
         }
@@ -285,6 +356,7 @@ impl JSValue {
             JSValue::Undefined => "undefined",
             JSValue::String(_) => "string",
             JSValue::Number(_) => "number",
+            JSValue::BigInt(_) => "bigint",
             JSValue::Bool(_) => "boolean",
             JSValue::Ref(r) => match heap.get(*r).is_callable() {
                 true => "function",
@@ -295,7 +367,7 @@ impl JSValue {
 
     /// Abstract Equality Comparison, `==`:
     /// <https://developer.mozilla.org/en-US/docs/Web/JavaScript/Equality_comparisons_and_sameness#Loose_equality_using_>
-    pub fn loose_eq(&self, other: &JSValue, heap: &Heap) -> bool {
+    pub fn loose_eq(&self, other: &JSValue, heap: &mut Heap) -> JSResult<bool> {
         let lval = if self == &JSValue::NULL {
             &JSValue::Undefined
         } else {
@@ -307,23 +379,30 @@ impl JSValue {
             other
         };
         match (lval, rval) {
-            (JSValue::Undefined, JSValue::Undefined) => true,
-            (JSValue::Undefined, _) | (_, JSValue::Undefined) => false,
+            (JSValue::Undefined, JSValue::Undefined) => Ok(true),
+            (JSValue::Undefined, _) | (_, JSValue::Undefined) => Ok(false),
             (JSValue::Number(_), JSValue::Number(_))
+            | (JSValue::BigInt(_), JSValue::BigInt(_))
             | (JSValue::String(_), JSValue::String(_))
-            | (JSValue::Bool(_), JSValue::Bool(_)) => self == other,
-            (JSValue::Ref(lref), JSValue::Ref(rref)) if lref == rref => true,
-            (JSValue::Ref(lref), JSValue::Ref(rref)) => match (
-                heap.get(*lref).to_primitive(),
-                heap.get(*rref).to_primitive(),
-            ) {
-                (Some(lval), Some(rval)) => lval == rval,
-                _ => false,
-            },
-            _ => match (self.numberify(heap), other.numberify(heap)) {
+            | (JSValue::Bool(_), JSValue::Bool(_)) => Ok(self == other),
+            // two objects are equal only if they are the same object, even wrappers
+            (JSValue::Ref(lref), JSValue::Ref(rref)) => Ok(lref == rref),
+            // an object is compared by its primitive value
+            (JSValue::Ref(_), primitive) => {
+                let value = lval.to_primitive(PrimitiveHint::Default, heap)?;
+                value.loose_eq(primitive, heap)
+            }
+            (primitive, JSValue::Ref(_)) => {
+                let value = rval.to_primitive(PrimitiveHint::Default, heap)?;
+                primitive.loose_eq(&value, heap)
+            }
+            (JSValue::BigInt(big), primitive) | (primitive, JSValue::BigInt(big)) => {
+                Ok(bigint_cmp(big, primitive, heap) == Some(Ordering::Equal))
+            }
+            _ => Ok(match (self.numberify(heap), other.numberify(heap)) {
                 (Some(lnum), Some(rnum)) => lnum == rnum,
                 _ => false,
-            },
+            }),
         }
     }
 
@@ -333,68 +412,191 @@ impl JSValue {
         self == other
     }
 
-    pub fn numerically<F>(&self, other: &JSValue, heap: &Heap, op: F) -> JSValue
+    /// SameValueZero: strict equality, except that `NaN` equals `NaN`. Used by `.includes()`.
+    /// <https://developer.mozilla.org/en-US/docs/Web/JavaScript/Equality_comparisons_and_sameness#same-value-zero_equality>
+    pub fn same_value_zero(&self, other: &JSValue) -> bool {
+        match (self, other) {
+            (JSValue::Number(l), JSValue::Number(r)) if l.is_nan() && r.is_nan() => true,
+            _ => self == other,
+        }
+    }
+
+    pub fn numerically<F>(&self, other: &JSValue, heap: &mut Heap, op: F) -> JSResult<JSValue>
     where
         F: Fn(f64, f64) -> f64,
     {
-        let val = match (self.numberify(heap), other.numberify(heap)) {
-            (Some(lnum), Some(rnum)) => op(lnum, rnum),
-            _ => f64::NAN,
-        };
-        JSValue::Number(val)
+        let lnum = self.to_number(heap)?;
+        let rnum = other.to_number(heap)?;
+        Ok(JSValue::Number(op(lnum, rnum)))
+    }
+
+    /// Arithmetic on numbers with `numop` or on two bigints with `bigop`.
+    /// Mixing a bigint with anything else is a TypeError.
+    pub fn arithmetic<F, G>(
+        &self,
+        other: &JSValue,
+        heap: &mut Heap,
+        numop: F,
+        bigop: G,
+    ) -> JSResult<JSValue>
+    where
+        F: Fn(f64, f64) -> f64,
+        G: Fn(&JSBigInt, &JSBigInt) -> JSResult<JSBigInt>,
+    {
+        let lval = self.to_primitive(PrimitiveHint::Number, heap)?;
+        let rval = other.to_primitive(PrimitiveHint::Number, heap)?;
+        match (&lval, &rval) {
+            (JSValue::BigInt(l), JSValue::BigInt(r)) => Ok(JSValue::BigInt(bigop(l, r)?)),
+            (JSValue::BigInt(_), _) | (_, JSValue::BigInt(_)) => {
+                Err(Exception::type_error(TypeError::BIGINT_MIX, rval))
+            }
+            _ => JSValue::numerically(&lval, &rval, heap, numop),
+        }
     }
 
     /// Addition operator:
     /// <https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Operators/Addition>
     pub fn plus(&self, other: &JSValue, heap: &mut Heap) -> JSResult<JSValue> {
-        if let JSValue::String(str_self) = self {
-            let str_other = other.stringify(heap)?;
-            let mut result = String::from(str_self.as_str());
-            result.push_str(str_other.as_str());
-            return Ok(JSValue::from(result));
-        }
-        if let JSValue::String(str_other) = other {
-            let str_self = self.stringify(heap)?;
-            let mut result = String::from(str_self.as_str());
-            result.push_str(str_other.as_str());
+        let lval = self.to_primitive(PrimitiveHint::Default, heap)?;
+        let rval = other.to_primitive(PrimitiveHint::Default, heap)?;
+        if let (JSValue::String(_), _) | (_, JSValue::String(_)) = (&lval, &rval) {
+            let mut result = String::from(lval.stringify(heap)?.as_str());
+            result.push_str(rval.stringify(heap)?.as_str());
             return Ok(JSValue::from(result));
         }
-        let num_self = JSValue::Undefined
-            .eq(self)
-            .then_some(f64::NAN)
-            .or_else(|| self.numberify(heap));
-        let num_other = JSValue::Undefined
-            .eq(other)
-            .then_some(f64::NAN)
-            .or_else(|| other.numberify(heap));
-        if let (Some(lnum), Some(rnum)) = (num_self, num_other) {
-            return Ok(JSValue::from(lnum + rnum));
-        }
-        let lvalstr = self.stringify(heap)?;
-        let rvalstr = other.stringify(heap)?;
-        Ok(JSValue::from(lvalstr.to_string() + rvalstr.as_str()))
+        JSValue::arithmetic(&lval, &rval, heap, |a, b| a + b, |a, b| Ok(a + b))
     }
 
     /// Subtraction operator:
     /// <https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Operators/Subtraction>
-    pub fn minus(&self, other: &JSValue, heap: &Heap) -> JSResult<JSValue> {
-        Ok(JSValue::numerically(self, other, heap, |a, b| a - b))
+    pub fn minus(&self, other: &JSValue, heap: &mut Heap) -> JSResult<JSValue> {
+        JSValue::arithmetic(self, other, heap, |a, b| a - b, |a, b| Ok(a - b))
     }
 
+    /// Abstract Relational Comparison, `<`, `>`, `<=`, `>=`: both values are converted
+    /// with [`JSValue::to_primitive`], two strings are compared as strings, anything else as numbers.
     pub fn compare<StrCmpFn: Fn(&str, &str) -> bool, NumCmpFn: Fn(f64, f64) -> bool>(
         &self,
         other: &JSValue,
-        heap: &Heap,
+        heap: &mut Heap,
         stringly: StrCmpFn,
         numberly: NumCmpFn,
-    ) -> JSValue {
-        // TODO: Abstract Relational Comparison
-        if let (JSValue::String(lstr), JSValue::String(rstr)) = (self, other) {
-            return JSValue::from(stringly(lstr.as_str(), rstr.as_str()));
+    ) -> JSResult<JSValue> {
+        let lval = self.to_primitive(PrimitiveHint::Number, heap)?;
+        let rval = other.to_primitive(PrimitiveHint::Number, heap)?;
+        if let (JSValue::String(lstr), JSValue::String(rstr)) = (&lval, &rval) {
+            return Ok(JSValue::from(stringly(lstr.as_str(), rstr.as_str())));
+        };
+        // bigints are compared exactly, by the sign of their ordering; NaN if incomparable
+        let ordering = match (&lval, &rval) {
+            (JSValue::BigInt(l), JSValue::BigInt(r)) => Some(l.cmp(r)),
+            (JSValue::BigInt(l), r) => bigint_cmp(l, r, heap),
+            (l, JSValue::BigInt(r)) => bigint_cmp(r, l, heap).map(Ordering::reverse),
+            _ => {
+                let lnum = lval.numberify(heap).unwrap_or(f64::NAN);
+                let rnum = rval.numberify(heap).unwrap_or(f64::NAN);
+                return Ok(JSValue::from(numberly(lnum, rnum)));
+            }
         };
-        let lnum = self.numberify(heap).unwrap_or(f64::NAN);
-        let rnum = other.numberify(heap).unwrap_or(f64::NAN);
-        JSValue::from(numberly(lnum, rnum))
+        let ordering = ordering.map_or(f64::NAN, |ord| ord as i8 as f64);
+        Ok(JSValue::from(numberly(ordering, 0.0)))
+    }
+}
+
+/// Compares a bigint with another primitive exactly: a string is parsed as a bigint,
+/// anything else is a number. `None` if they are incomparable, e.g. with `NaN`.
+fn bigint_cmp(big: &JSBigInt, other: &JSValue, heap: &Heap) -> Option<Ordering> {
+    if let JSValue::String(s) = other {
+        let s = s.as_str().trim();
+        let other = if s.is_empty() {
+            Some(JSBigInt::zero())
+        } else {
+            s.parse().ok()
+        };
+        return other.map(|other| big.cmp(&other));
+    }
+    let num = other.numberify(heap)?;
+    if num.is_nan() {
+        return None;
+    }
+    if num.is_infinite() {
+        return Some(if num > 0.0 {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        });
+    }
+    // `big` is compared with the integer part, then with the fractional one
+    let floor = JSBigInt::from_f64(num.floor())?;
+    match big.cmp(&floor) {
+        Ordering::Equal if num.fract() != 0.0 => Some(Ordering::Less),
+        ordering => Some(ordering),
+    }
+}
+
+/// The largest bigint, in bits, like in V8.
+const BIGINT_MAX_BITS: u64 = 1 << 30;
+
+/// The divisor of `/` and `%` for bigints: `1n / 0n` is a RangeError.
+pub(crate) fn bigint_divisor(divisor: &JSBigInt) -> JSResult<&JSBigInt> {
+    if divisor.is_zero() {
+        let what = JSValue::BigInt(divisor.clone());
+        return Err(Exception::range_error(RangeError::DIVISION_BY_ZERO, what));
+    }
+    Ok(divisor)
+}
+
+/// `base ** exponent` for bigints: the exponent cannot be negative
+/// and the result cannot be larger than `BIGINT_MAX_BITS`.
+pub(crate) fn bigint_pow(base: &JSBigInt, exponent: &JSBigInt) -> JSResult<JSBigInt> {
+    let what = || JSValue::BigInt(exponent.clone());
+    if exponent.is_negative() {
+        return Err(Exception::range_error(
+            RangeError::NEGATIVE_EXPONENT,
+            what(),
+        ));
+    }
+    // `0n`, `1n` and `-1n` only care about the parity of a huge exponent
+    let small_base = base.bits() <= 1;
+    let exponent = (exponent.to_u32())
+        .or_else(|| small_base.then(|| 2 + exponent.bit(0) as u32))
+        .filter(|e| base.bits() * (*e as u64) <= BIGINT_MAX_BITS)
+        .ok_or_else(|| Exception::range_error(RangeError::BIGINT_TOO_BIG, what()))?;
+    Ok(base.pow(exponent))
+}
+
+/// ES5: ToNumber applied to the String type, `None` means `NaN`.
+/// Surrounding whitespace is ignored, an empty string is `0`.
+fn parse_number(s: &str) -> Option<JSNumber> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Some(0.0);
+    }
+    let radix = match s.get(..2) {
+        Some("0x") | Some("0X") => 16,
+        Some("0o") | Some("0O") => 8,
+        Some("0b") | Some("0B") => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        return u64::from_str_radix(&s[2..], radix)
+            .ok()
+            .map(|n| n as JSNumber);
+    }
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+    if unsigned == "Infinity" {
+        let infinity = if s.starts_with('-') {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        };
+        return Some(infinity);
+    }
+    // Rust also accepts "inf", "NaN", etc.
+    let valid = |c: char| c.is_ascii_digit() || "+-.eE".contains(c);
+    match s.chars().all(valid) {
+        true => s.parse::<JSNumber>().ok(),
+        false => None,
     }
 }
 
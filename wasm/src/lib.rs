@@ -32,16 +32,38 @@ fn jserror<E: fmt::Debug>(e: E) -> JsValue {
     JsValue::from(format!("{:?}", e))
 }
 
+/// Streams output to `callback` line by line as it is printed, instead of only returning
+/// the final result from [`interpret`], e.g. to `postMessage()` it from a worker.
+/// `console.log()` lines come as `callback(line, "stdout")`,
+/// `console.error()`/`console.warn()` ones as `callback(line, "stderr")`.
+/// Exceptions thrown by `callback` are ignored.
+#[wasm_bindgen(js_name = setOutputCallback)]
+pub fn set_output_callback(callback: js_sys::Function) {
+    let sink = |stream: &'static str| {
+        let callback = callback.clone();
+        Box::new(move |line: &str| {
+            let _ = callback.call2(&JsValue::NULL, &JsValue::from(line), &JsValue::from(stream));
+        })
+    };
+    HEAP.with(|heapcell| {
+        let mut heap = heapcell.borrow_mut();
+        heap.set_stdout(sink("stdout"));
+        heap.set_stderr(sink("stderr"));
+    });
+}
+
 /// Takes a ESTree AST representation and produces a result as a pretty-printed string
 #[wasm_bindgen]
 pub fn interpret(jsobject: &JsValue) -> Result<JsValue, JsValue> {
     let json: JSON = jsobject.into_serde().map_err(jserror)?;
     let program = Program::parse_from(&json).map_err(jserror)?;
-    let result = HEAP
-        .with(|heapcell| {
-            let mut heap = heapcell.borrow_mut();
-            heap.evaluate(&program)?.to_string(&mut heap)
-        })
-        .map_err(jserror)?;
+    let result = HEAP.with(|heapcell| {
+        let mut heap = heapcell.borrow_mut();
+        let result = heap.evaluate(&program).and_then(|result| {
+            heap.run_microtasks()?;
+            result.to_string(&mut heap)
+        });
+        result.map_err(|exc| JsValue::from(heap.format_exception(&exc)))
+    })?;
     JsValue::from_serde(result.as_str()).map_err(jserror)
 }
@@ -25,3 +25,121 @@ fn test_interpret() {
     let x_plus = JsValue::from_serde(&x_plus).unwrap();
     assert_eq!(sljs_wasm::interpret(&x_plus), Ok(JsValue::from("20")));
 }
+
+#[wasm_bindgen_test]
+fn test_interpret_built_loop() {
+    use sljs::ast::{expr, stmt, BinOp};
+
+    // for (var i = 0, sum = 0; i < 5; i = i + 1) sum = sum + i;  sum
+    let program = sljs::Program::from_stmts(vec![
+        stmt::for_(
+            Some(stmt::var([("i", expr::lit(0)), ("sum", expr::lit(0))].iter()).into()),
+            Some(expr::binary(BinOp::Less, expr::id("i"), 5)),
+            Some(expr::assign(expr::id("i"), expr::add(expr::id("i"), 1))),
+            stmt::expr(expr::assign(
+                expr::id("sum"),
+                expr::add(expr::id("sum"), expr::id("i")),
+            )),
+        ),
+        stmt::expr(expr::id("sum")),
+    ])
+    .to_estree();
+
+    let program = JsValue::from_serde(&program).unwrap();
+    assert_eq!(sljs_wasm::interpret(&program), Ok(JsValue::from("10")));
+}
+
+#[wasm_bindgen_test]
+fn test_interpret_runs_microtasks() {
+    use serde_json::json;
+    use sljs::ast::expr;
+
+    // var seen = 1; queueMicrotask(function () { seen = 2; });
+    let schedule = json!({
+        "type": "Program",
+        "body": [
+            {
+                "type": "VariableDeclaration",
+                "kind": "var",
+                "declarations": [{
+                    "type": "VariableDeclarator",
+                    "id": {"type": "Identifier", "name": "seen"},
+                    "init": {"type": "Literal", "value": 1},
+                }],
+            },
+            {
+                "type": "ExpressionStatement",
+                "expression": {
+                    "type": "CallExpression",
+                    "callee": {"type": "Identifier", "name": "queueMicrotask"},
+                    "arguments": [{
+                        "type": "FunctionExpression",
+                        "id": null,
+                        "params": [],
+                        "body": {
+                            "type": "BlockStatement",
+                            "body": [{
+                                "type": "ExpressionStatement",
+                                "expression": {
+                                    "type": "AssignmentExpression",
+                                    "operator": "=",
+                                    "left": {"type": "Identifier", "name": "seen"},
+                                    "right": {"type": "Literal", "value": 2},
+                                },
+                            }],
+                        },
+                    }],
+                },
+            },
+        ],
+    });
+    let seen = sljs::Program::from_stmt(expr::id("seen")).to_estree();
+
+    sljs_wasm::interpret(&JsValue::from_serde(&schedule).unwrap()).unwrap();
+    let result = sljs_wasm::interpret(&JsValue::from_serde(&seen).unwrap());
+    assert_eq!(result, Ok(JsValue::from("2")));
+}
+
+#[wasm_bindgen_test]
+fn test_output_callback() {
+    use std::{cell::RefCell, rc::Rc};
+
+    use sljs::ast::{expr, stmt};
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let lines = Rc::new(RefCell::new(vec![]));
+    let collector = {
+        let lines = Rc::clone(&lines);
+        Closure::wrap(Box::new(move |line: String, stream: String| {
+            lines.borrow_mut().push(format!("{}: {}", stream, line));
+        }) as Box<dyn FnMut(String, String)>)
+    };
+    sljs_wasm::set_output_callback(
+        collector
+            .as_ref()
+            .unchecked_ref::<js_sys::Function>()
+            .clone(),
+    );
+
+    // console.log('one'); console.log('two', 2); console.error('three'); 'done'
+    let console = |method: &str, args: Vec<sljs::ast::Expression>| {
+        stmt::expr(expr::call(expr::memb(expr::id("console"), method), args))
+    };
+    let program = sljs::Program::from_stmts(vec![
+        console("log", vec![expr::lit("one")]),
+        console("log", vec![expr::lit("two"), expr::lit(2)]),
+        console("error", vec![expr::lit("three")]),
+        stmt::expr(expr::lit("done")),
+    ])
+    .to_estree();
+
+    let program = JsValue::from_serde(&program).unwrap();
+    assert_eq!(
+        sljs_wasm::interpret(&program),
+        Ok(JsValue::from("\"done\""))
+    );
+    assert_eq!(
+        *lines.borrow(),
+        vec!["stdout: one", "stdout: two 2", "stderr: three"]
+    );
+}